@@ -1 +1,78 @@
+use std::fmt;
 
+/// What can go wrong in this crate outside of programming errors.
+#[derive(Debug)]
+pub enum Error {
+    /// The platform refused to create a native window.
+    WindowCreation(winit::error::OsError),
+    /// No drawing surface could be created for a window.
+    SurfaceCreation(wgpu::CreateSurfaceError),
+    /// No graphics adapter can draw to the window.
+    AdapterNotFound,
+    /// The graphics adapter did not hand out a device.
+    DeviceRequest(wgpu::RequestDeviceError),
+    /// Drawing to a window failed. A surface that was lost or outdated is
+    /// reconfigured, so the next frame may succeed.
+    Surface(wgpu::SurfaceError),
+    /// A font file could not be read, or parsed by ab_glyph.
+    Font(Box<dyn std::error::Error + Send + Sync>),
+    /// An image file could not be read, or decoded as a PNG.
+    Image(Box<dyn std::error::Error + Send + Sync>),
+    /// A widget tree that cannot be laid out.
+    Layout(String),
+}
+
+/// A [`Result`](std::result::Result) failing with this crate's [`Error`].
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::WindowCreation(error) => write!(f, "window could not be created: {}", error),
+            Error::SurfaceCreation(error) => write!(f, "surface could not be created: {}", error),
+            Error::AdapterNotFound => write!(f, "no suitable graphics adapter was found"),
+            Error::DeviceRequest(error) => write!(f, "graphics device request failed: {}", error),
+            Error::Surface(error) => write!(f, "drawing to the surface failed: {}", error),
+            Error::Font(error) => write!(f, "font could not be loaded: {}", error),
+            Error::Image(error) => write!(f, "image could not be loaded: {}", error),
+            Error::Layout(message) => write!(f, "layout failed: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::WindowCreation(error) => Some(error),
+            Error::SurfaceCreation(error) => Some(error),
+            Error::DeviceRequest(error) => Some(error),
+            Error::Surface(error) => Some(error),
+            Error::Font(error) | Error::Image(error) => Some(&**error),
+            Error::AdapterNotFound | Error::Layout(_) => None,
+        }
+    }
+}
+
+impl From<winit::error::OsError> for Error {
+    fn from(error: winit::error::OsError) -> Self {
+        Error::WindowCreation(error)
+    }
+}
+
+impl From<wgpu::CreateSurfaceError> for Error {
+    fn from(error: wgpu::CreateSurfaceError) -> Self {
+        Error::SurfaceCreation(error)
+    }
+}
+
+impl From<wgpu::RequestDeviceError> for Error {
+    fn from(error: wgpu::RequestDeviceError) -> Self {
+        Error::DeviceRequest(error)
+    }
+}
+
+impl From<wgpu::SurfaceError> for Error {
+    fn from(error: wgpu::SurfaceError) -> Self {
+        Error::Surface(error)
+    }
+}
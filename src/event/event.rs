@@ -1,29 +1,226 @@
-// TODO! Replace Placeholder
+use std::{
+    cell::RefCell,
+    rc::{Rc, Weak},
+};
 
+/// What a listener wants to happen after it handled an event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Propagation {
+    /// Let the remaining listeners see the event.
+    #[default]
+    Continue,
+    /// Keep the event from the remaining listeners.
+    Stop,
+}
+
+/// Listeners that return nothing let the event propagate.
+impl From<()> for Propagation {
+    fn from(_: ()) -> Self {
+        Propagation::Continue
+    }
+}
+
+type Callback<T> = Rc<RefCell<dyn FnMut(T) -> Propagation>>;
+
+struct Listener<T> {
+    id: u64,
+    priority: i32,
+    once: bool,
+    callback: Callback<T>,
+}
+
+struct Listeners<T> {
+    next_id: u64,
+    /// Sorted from the highest priority, then in the order of subscription.
+    listeners: Vec<Listener<T>>,
+}
+
+impl<T> Listeners<T> {
+    fn remove(&mut self, id: u64) -> bool {
+        let count = self.listeners.len();
+        self.listeners.retain(|listener| listener.id != id);
+        self.listeners.len() != count
+    }
+}
+
+/// A list of listeners that are called, one after the other, whenever the
+/// event is triggered.
 pub struct Event<T> {
-    callbacks: Vec<Box<dyn Fn(T) + 'static>>,
+    inner: Rc<RefCell<Listeners<T>>>,
+}
+
+impl<T> std::fmt::Debug for Event<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Event")
+            .field("callbacks", &self.inner.borrow().listeners.len())
+            .finish()
+    }
+}
+
+impl<T> Default for Event<T>
+where
+    T: Clone + 'static,
+{
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl<T> Event<T>
 where
-    T: Clone,
+    T: Clone + 'static,
 {
     pub fn new() -> Self {
         Self {
-            callbacks: Vec::new(),
+            inner: Rc::new(RefCell::new(Listeners {
+                next_id: 0,
+                listeners: Vec::new(),
+            })),
+        }
+    }
+
+    /// Adds a listener, which is removed when the returned [`Subscription`]
+    /// is dropped. The listener can return a [`Propagation`] to stop the
+    /// event from reaching the listeners after it.
+    pub fn subscribe<F, R>(&self, callback: F) -> Subscription
+    where
+        F: FnMut(T) -> R + 'static,
+        R: Into<Propagation>,
+    {
+        self.add(0, false, callback)
+    }
+
+    /// Adds a listener that is called before those of a lower priority.
+    /// Listeners of the same priority are called in the order they
+    /// subscribed, the default priority being 0.
+    pub fn subscribe_with_priority<F, R>(&self, priority: i32, callback: F) -> Subscription
+    where
+        F: FnMut(T) -> R + 'static,
+        R: Into<Propagation>,
+    {
+        self.add(priority, false, callback)
+    }
+
+    /// Adds a listener that is removed after it is called once.
+    pub fn once<F, R>(&self, callback: F) -> Subscription
+    where
+        F: FnOnce(T) -> R + 'static,
+        R: Into<Propagation>,
+    {
+        let mut callback = Some(callback);
+        self.add(0, true, move |event| match callback.take() {
+            Some(callback) => callback(event).into(),
+            None => Propagation::Continue,
+        })
+    }
+
+    /// The number of listeners.
+    pub fn len(&self) -> usize {
+        self.inner.borrow().listeners.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Calls the listeners with a clone of `event` each, until one of them
+    /// stops the propagation. Listeners added or removed by a listener take
+    /// effect from the next trigger.
+    pub fn trigger(&self, event: T) -> Propagation {
+        let listeners: Vec<_> = self
+            .inner
+            .borrow()
+            .listeners
+            .iter()
+            .map(|listener| (listener.id, listener.once, listener.callback.clone()))
+            .collect();
+        for (id, once, callback) in listeners {
+            let subscribed = self
+                .inner
+                .borrow()
+                .listeners
+                .iter()
+                .any(|listener| listener.id == id);
+            if !subscribed {
+                continue;
+            }
+            if once {
+                self.inner.borrow_mut().remove(id);
+            }
+            // A listener that triggers its own event is not called again.
+            let Ok(mut callback) = callback.try_borrow_mut() else {
+                continue;
+            };
+            if callback(event.clone()) == Propagation::Stop {
+                return Propagation::Stop;
+            }
         }
+        Propagation::Continue
     }
 
-    pub fn subscribe<'a, F>(&'a mut self, callback: F)
+    fn add<F, R>(&self, priority: i32, once: bool, mut callback: F) -> Subscription
     where
-        F: Fn(T) + 'a + 'static,
+        F: FnMut(T) -> R + 'static,
+        R: Into<Propagation>,
     {
-        self.callbacks.push(Box::new(callback));
+        let mut inner = self.inner.borrow_mut();
+        let id = inner.next_id;
+        inner.next_id += 1;
+        let index = inner
+            .listeners
+            .iter()
+            .position(|listener| listener.priority < priority)
+            .unwrap_or(inner.listeners.len());
+        let callback: Callback<T> = Rc::new(RefCell::new(move |event| callback(event).into()));
+        inner.listeners.insert(
+            index,
+            Listener {
+                id,
+                priority,
+                once,
+                callback,
+            },
+        );
+
+        let listeners: Weak<RefCell<Listeners<T>>> = Rc::downgrade(&self.inner);
+        Subscription {
+            unsubscribe: Some(Box::new(move || {
+                if let Some(listeners) = listeners.upgrade() {
+                    listeners.borrow_mut().remove(id);
+                }
+            })),
+        }
     }
+}
+
+/// Keeps a listener subscribed to an [`Event`] for as long as it is alive.
+#[must_use = "dropping a Subscription removes the listener, call `detach` to keep it"]
+pub struct Subscription {
+    unsubscribe: Option<Box<dyn FnOnce()>>,
+}
+
+impl Subscription {
+    /// Keeps the listener subscribed for as long as the event exists.
+    pub fn detach(mut self) {
+        self.unsubscribe = None;
+    }
+
+    /// Removes the listener, the same as dropping the subscription.
+    pub fn unsubscribe(self) {}
+}
+
+impl std::fmt::Debug for Subscription {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Subscription")
+            .field("detached", &self.unsubscribe.is_none())
+            .finish()
+    }
+}
 
-    pub fn trigger(&self, event: T) {
-        for callback in &self.callbacks {
-            callback(event.clone());
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        if let Some(unsubscribe) = self.unsubscribe.take() {
+            unsubscribe();
         }
     }
 }
@@ -0,0 +1,117 @@
+use super::{dispatch_key, FocusEvent, Key, KeyEvent, KeyEventKind, Modifiers};
+use crate::widget::Widget;
+
+/// The widget at the end of `path`, a list of child indices from `root`.
+pub fn descendant<'a>(root: &'a mut dyn Widget, path: &[usize]) -> Option<&'a mut dyn Widget> {
+    match path.split_first() {
+        None => Some(root),
+        Some((&index, rest)) => descendant(root.children_mut().get_mut(index)?.as_mut(), rest),
+    }
+}
+
+/// The paths of every focusable widget under `root`, in Tab order: the
+/// order of the tree, parents before their children.
+pub fn focus_order(root: &dyn Widget) -> Vec<Vec<usize>> {
+    fn visit(widget: &dyn Widget, path: &mut Vec<usize>, order: &mut Vec<Vec<usize>>) {
+        if widget.focusable() {
+            order.push(path.clone());
+        }
+        for (index, child) in widget.children().iter().enumerate() {
+            path.push(index);
+            visit(child.as_ref(), path, order);
+            path.pop();
+        }
+    }
+    let mut order = Vec::new();
+    visit(root, &mut Vec::new(), &mut order);
+    order
+}
+
+/// Keeps track of the widget of one window that receives keyboard input.
+#[derive(Debug, Default)]
+pub struct FocusManager {
+    focused: Option<Vec<usize>>,
+}
+
+impl FocusManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The path of the focused widget, if any.
+    pub fn focused(&self) -> Option<&[usize]> {
+        self.focused.as_deref()
+    }
+
+    /// Moves the focus to the widget at `path`, sending blur and focus
+    /// events. Widgets that are not focusable only take the focus away.
+    pub fn focus(&mut self, root: &mut dyn Widget, path: Option<Vec<usize>>) {
+        let path = path.filter(|path| descendant(root, path).is_some_and(|w| w.focusable()));
+        if path == self.focused {
+            return;
+        }
+        if let Some(widget) = self.focused.take().and_then(|old| descendant(root, &old)) {
+            widget.on_focus(FocusEvent::Blur);
+        }
+        if let Some(widget) = path.as_ref().and_then(|new| descendant(root, new)) {
+            widget.on_focus(FocusEvent::Focus);
+        }
+        self.focused = path;
+    }
+
+    /// Focuses the innermost focusable widget along `path`, as when it is
+    /// clicked, or takes the focus away if there is none.
+    pub fn focus_within(&mut self, root: &mut dyn Widget, mut path: Vec<usize>) {
+        loop {
+            if descendant(root, &path).is_some_and(|widget| widget.focusable()) {
+                return self.focus(root, Some(path));
+            }
+            if path.pop().is_none() {
+                return self.focus(root, None);
+            }
+        }
+    }
+
+    /// Focuses the next focusable widget, wrapping around after the last.
+    pub fn focus_next(&mut self, root: &mut dyn Widget) {
+        self.step(root, true);
+    }
+
+    /// Focuses the previous focusable widget, wrapping around before the
+    /// first.
+    pub fn focus_previous(&mut self, root: &mut dyn Widget) {
+        self.step(root, false);
+    }
+
+    fn step(&mut self, root: &mut dyn Widget, forward: bool) {
+        let order = focus_order(root);
+        if order.is_empty() {
+            return self.focus(root, None);
+        }
+        let current = self
+            .focused
+            .as_ref()
+            .and_then(|focused| order.iter().position(|path| path == focused));
+        let next = match (current, forward) {
+            (Some(index), true) => (index + 1) % order.len(),
+            (Some(index), false) => (index + order.len() - 1) % order.len(),
+            (None, true) => 0,
+            (None, false) => order.len() - 1,
+        };
+        self.focus(root, Some(order[next].clone()));
+    }
+
+    /// Sends a keyboard event to the focused widget and its ancestors. Tab
+    /// and Shift+Tab move the focus unless a widget stopped the event.
+    pub fn handle(&mut self, root: &mut dyn Widget, kind: KeyEventKind, modifiers: Modifiers) {
+        let mut event = KeyEvent::new(kind, modifiers);
+        let path = self.focused.clone().unwrap_or_default();
+        dispatch_key(root, &path, &mut event);
+        if event.is_propagation_stopped() {
+            return;
+        }
+        if kind == KeyEventKind::Down(Key::Tab) {
+            self.step(root, !modifiers.shift);
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use super::{descendant, Phase};
+use crate::widget::Widget;
+
+/// A key on the keyboard, independent of the layout's modifiers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Key {
+    /// A key producing a character, letters in lower case.
+    Character(char),
+    Enter,
+    Tab,
+    Space,
+    Backspace,
+    Delete,
+    Escape,
+    Insert,
+    ArrowLeft,
+    ArrowRight,
+    ArrowUp,
+    ArrowDown,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    /// A function key, `F(1)` being F1.
+    F(u8),
+    Shift,
+    Control,
+    Alt,
+    /// The Windows, Command or Super key.
+    Logo,
+    Unidentified,
+}
+
+/// The modifier keys held down during a keyboard event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Modifiers {
+    pub shift: bool,
+    pub control: bool,
+    pub alt: bool,
+    pub logo: bool,
+}
+
+impl Modifiers {
+    pub const NONE: Modifiers = Modifiers {
+        shift: false,
+        control: false,
+        alt: false,
+        logo: false,
+    };
+    pub const SHIFT: Modifiers = Modifiers {
+        shift: true,
+        ..Modifiers::NONE
+    };
+
+    pub fn is_empty(&self) -> bool {
+        *self == Modifiers::NONE
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum KeyEventKind {
+    Down(Key),
+    Up(Key),
+    /// A character typed, after the keyboard layout and modifiers are applied.
+    Text(char),
+}
+
+/// A keyboard event as seen by one widget, on its way from the focused
+/// widget up to the root.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyEvent {
+    pub kind: KeyEventKind,
+    pub modifiers: Modifiers,
+    phase: Phase,
+    stopped: bool,
+}
+
+impl KeyEvent {
+    pub fn new(kind: KeyEventKind, modifiers: Modifiers) -> Self {
+        Self {
+            kind,
+            modifiers,
+            phase: Phase::Target,
+            stopped: false,
+        }
+    }
+
+    /// [`Phase::Target`] for the focused widget, [`Phase::Bubble`] for its
+    /// ancestors.
+    pub fn phase(&self) -> Phase {
+        self.phase
+    }
+
+    /// Keeps the event from reaching any further widget, including the
+    /// default handling such as Tab navigation.
+    pub fn stop_propagation(&mut self) {
+        self.stopped = true;
+    }
+
+    pub fn is_propagation_stopped(&self) -> bool {
+        self.stopped
+    }
+}
+
+/// Delivers `event` to the widget at the end of `path`, then to each of its
+/// ancestors up to `root`, until a handler stops its propagation.
+pub fn dispatch_key(root: &mut dyn Widget, path: &[usize], event: &mut KeyEvent) {
+    for depth in (0..=path.len()).rev() {
+        if event.stopped {
+            break;
+        }
+        let Some(widget) = descendant(root, &path[..depth]) else {
+            continue;
+        };
+        event.phase = if depth == path.len() {
+            Phase::Target
+        } else {
+            Phase::Bubble
+        };
+        widget.on_key(event);
+    }
+}
+
+/// Sent to a widget when it gains or loses the keyboard focus.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FocusEvent {
+    Focus,
+    Blur,
+}
@@ -1,3 +1,9 @@
 pub mod event;
+pub mod focus;
+pub mod keyboard;
+pub mod pointer;
 pub mod window;
 pub use event::*;
+pub use focus::*;
+pub use keyboard::*;
+pub use pointer::*;
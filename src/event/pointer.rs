@@ -0,0 +1,210 @@
+use crate::layout::LayoutNode;
+use crate::widget::Widget;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PointerButton {
+    Primary,
+    Secondary,
+    Middle,
+    Other(u16),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PointerEventKind {
+    Move,
+    Down(PointerButton),
+    Up(PointerButton),
+    /// Scrolling by the given number of pixels.
+    Wheel {
+        delta_x: f32,
+        delta_y: f32,
+    },
+    /// The pointer moved onto the widget. Not propagated.
+    Enter,
+    /// The pointer moved off the widget. Not propagated.
+    Leave,
+}
+
+/// Where a [`PointerEvent`] is on its way through the widget tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    /// Travelling from the root down to the parent of the target.
+    Capture,
+    /// Delivered to the widget under the pointer.
+    Target,
+    /// Travelling from the parent of the target back up to the root.
+    Bubble,
+}
+
+/// A pointer event as seen by one widget, in window coordinates.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PointerEvent {
+    pub kind: PointerEventKind,
+    pub x: f32,
+    pub y: f32,
+    phase: Phase,
+    stopped: bool,
+}
+
+impl PointerEvent {
+    pub fn new(kind: PointerEventKind, x: f32, y: f32) -> Self {
+        Self {
+            kind,
+            x,
+            y,
+            phase: Phase::Target,
+            stopped: false,
+        }
+    }
+
+    pub fn phase(&self) -> Phase {
+        self.phase
+    }
+
+    /// Keeps the event from reaching any further widget.
+    pub fn stop_propagation(&mut self) {
+        self.stopped = true;
+    }
+
+    pub fn is_propagation_stopped(&self) -> bool {
+        self.stopped
+    }
+}
+
+/// The path of child indices from `root` to the top-most widget containing
+/// the point, or `None` when the point is outside of `root`.
+///
+/// `layout` is the result of arranging `root`.
+pub fn hit_test(root: &dyn Widget, layout: &LayoutNode, x: f32, y: f32) -> Option<Vec<usize>> {
+    if !layout.rect.contains(x, y) {
+        return None;
+    }
+    let children = root.children();
+    // Later painted children are on top, so they are hit first.
+    for index in root.paint_order().into_iter().rev() {
+        let (Some(child), Some(node)) = (children.get(index), layout.children.get(index)) else {
+            continue;
+        };
+        if let Some(mut path) = hit_test(child.as_ref(), node, x, y) {
+            path.insert(0, index);
+            return Some(path);
+        }
+    }
+    Some(Vec::new())
+}
+
+/// The widget at the end of `path`, and where it was laid out.
+pub fn widget_at<'a, 'b>(
+    root: &'a mut dyn Widget,
+    layout: &'b LayoutNode,
+    path: &[usize],
+) -> Option<(&'a mut dyn Widget, &'b LayoutNode)> {
+    match path.split_first() {
+        None => Some((root, layout)),
+        Some((&index, rest)) => {
+            let child = root.children_mut().get_mut(index)?;
+            widget_at(child.as_mut(), layout.children.get(index)?, rest)
+        }
+    }
+}
+
+/// Delivers `event` along `path`: down through the ancestors in the capture
+/// phase, to the target, then back up in the bubble phase, until a handler
+/// stops its propagation.
+pub fn dispatch_pointer(
+    root: &mut dyn Widget,
+    layout: &LayoutNode,
+    path: &[usize],
+    event: &mut PointerEvent,
+) {
+    let phases = (0..path.len())
+        .map(|depth| (depth, Phase::Capture))
+        .chain(std::iter::once((path.len(), Phase::Target)))
+        .chain((0..path.len()).rev().map(|depth| (depth, Phase::Bubble)));
+    for (depth, phase) in phases {
+        if event.stopped {
+            break;
+        }
+        let Some((widget, node)) = widget_at(root, layout, &path[..depth]) else {
+            break;
+        };
+        event.phase = phase;
+        widget.on_pointer(event, node.rect);
+    }
+}
+
+/// Routes raw pointer input to the widgets of one window, keeping track of
+/// which widgets the pointer is over.
+#[derive(Debug, Default)]
+pub struct PointerRouter {
+    hovered: Option<Vec<usize>>,
+}
+
+impl PointerRouter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The path of the widget under the pointer, see [`hit_test`].
+    pub fn hovered(&self) -> Option<&[usize]> {
+        self.hovered.as_deref()
+    }
+
+    /// Hit-tests and dispatches one pointer event, sending enter and leave
+    /// events to the widgets the pointer moved onto or off.
+    pub fn handle(
+        &mut self,
+        root: &mut dyn Widget,
+        layout: &LayoutNode,
+        kind: PointerEventKind,
+        x: f32,
+        y: f32,
+    ) {
+        if kind == PointerEventKind::Leave {
+            return self.hover(root, layout, None, x, y);
+        }
+        let path = hit_test(root, layout, x, y);
+        self.hover(root, layout, path.clone(), x, y);
+        if let Some(path) = path {
+            dispatch_pointer(root, layout, &path, &mut PointerEvent::new(kind, x, y));
+        }
+    }
+
+    fn hover(
+        &mut self,
+        root: &mut dyn Widget,
+        layout: &LayoutNode,
+        path: Option<Vec<usize>>,
+        x: f32,
+        y: f32,
+    ) {
+        // Depths are counted in widgets, the root being the first one.
+        let depth = |path: &Option<Vec<usize>>| path.as_ref().map_or(0, |path| path.len() + 1);
+        let common = match (&self.hovered, &path) {
+            (Some(old), Some(new)) => {
+                1 + old
+                    .iter()
+                    .zip(new.iter())
+                    .take_while(|(a, b)| a == b)
+                    .count()
+            }
+            _ => 0,
+        };
+        let mut notify = |path: &[usize], kind| {
+            if let Some((widget, node)) = widget_at(root, layout, path) {
+                widget.on_pointer(&mut PointerEvent::new(kind, x, y), node.rect);
+            }
+        };
+        if let Some(old) = &self.hovered {
+            for end in (common..depth(&self.hovered)).rev() {
+                notify(&old[..end], PointerEventKind::Leave);
+            }
+        }
+        if let Some(new) = &path {
+            for end in common..depth(&path) {
+                notify(&new[..end], PointerEventKind::Enter);
+            }
+        }
+        self.hovered = path;
+    }
+}
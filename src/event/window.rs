@@ -1,6 +1,77 @@
-#[derive(Clone)]
+use std::{cell::Cell, path::PathBuf, rc::Rc};
+
+use super::{KeyEventKind, Modifiers, PointerEventKind};
+use crate::style::Stylesheet;
+
+/// Whether the platform prefers light or dark colors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorScheme {
+    #[default]
+    Light,
+    Dark,
+}
+
+/// The user asked to close a window. Unless a handler cancels the request,
+/// for example to ask about unsaved changes first, the window is closed
+/// once every handler has seen it. Clones share the same request.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct CloseRequest {
+    cancelled: Rc<Cell<bool>>,
+}
+
+impl CloseRequest {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keeps the window open.
+    pub fn cancel(&self) {
+        self.cancelled.set(true);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.get()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub enum WindowEvent {
+    /// The content area was resized, in physical pixels.
     WindowResize(f32, f32),
+    /// The window was shown, before any other event.
     WindowOpened,
+    /// The user asked to close the window, followed by
+    /// [`WindowEvent::WindowClosed`] unless the request is cancelled.
+    CloseRequested(CloseRequest),
+    /// The window is closed, it receives no further events.
     WindowClosed,
+    /// The window's top-left corner moved to the given screen position.
+    WindowMoved(f32, f32),
+    /// The window gained the keyboard focus of the platform.
+    WindowFocused,
+    WindowUnfocused,
+    /// The window moved to a screen with a different scale factor, or the
+    /// user changed it. Followed by a [`WindowEvent::WindowResize`].
+    ScaleFactorChanged(f32),
+    WindowMinimized,
+    WindowMaximized,
+    /// The window is back to its normal state after being minimized or
+    /// maximized.
+    WindowRestored,
+    ThemeChanged(ColorScheme),
+    /// A file is dragged over the window. Sent once per file.
+    FileHovered(PathBuf),
+    /// The files dragged over the window left it without being dropped.
+    FileHoverCancelled,
+    /// A file was dropped onto the window. Sent once per file.
+    FileDropped(PathBuf),
+    /// The window is about to be redrawn.
+    RedrawRequested,
+    /// Pointer input at the given position in the window.
+    Pointer(PointerEventKind, f32, f32),
+    /// Keyboard input for the focused widget.
+    Keyboard(KeyEventKind, Modifiers),
+    /// Replaces the stylesheet of the window's widget tree, such as when its
+    /// file changed on disk.
+    StylesheetChanged(Stylesheet),
 }
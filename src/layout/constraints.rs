@@ -0,0 +1,132 @@
+use crate::utils::Rect;
+
+/// The range of sizes a parent allows one of its children to take.
+///
+/// The maximum may be infinite along an axis the parent does not bound,
+/// e.g. the main axis of a scrolling list.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Constraints {
+    pub min_width: f32,
+    pub min_height: f32,
+    pub max_width: f32,
+    pub max_height: f32,
+}
+
+impl Default for Constraints {
+    fn default() -> Self {
+        Self::UNBOUNDED
+    }
+}
+
+impl Constraints {
+    pub const UNBOUNDED: Constraints = Constraints {
+        min_width: 0.0,
+        min_height: 0.0,
+        max_width: f32::INFINITY,
+        max_height: f32::INFINITY,
+    };
+
+    /// Allows exactly `width` by `height`.
+    pub fn tight(width: f32, height: f32) -> Self {
+        Self {
+            min_width: width,
+            min_height: height,
+            max_width: width,
+            max_height: height,
+        }
+    }
+
+    /// Allows anything from nothing up to `width` by `height`.
+    pub fn loose(width: f32, height: f32) -> Self {
+        Self {
+            min_width: 0.0,
+            min_height: 0.0,
+            max_width: width,
+            max_height: height,
+        }
+    }
+
+    /// Clamps a desired size into the allowed range.
+    pub fn constrain(&self, width: f32, height: f32) -> (f32, f32) {
+        (
+            width.max(self.min_width).min(self.max_width),
+            height.max(self.min_height).min(self.max_height),
+        )
+    }
+
+    /// The constraints left after taking `width` and `height` off both the
+    /// minimum and the maximum, e.g. for padding.
+    pub fn deflate(&self, width: f32, height: f32) -> Self {
+        Self {
+            min_width: (self.min_width - width).max(0.0),
+            min_height: (self.min_height - height).max(0.0),
+            max_width: (self.max_width - width).max(0.0),
+            max_height: (self.max_height - height).max(0.0),
+        }
+    }
+
+    /// The space percentages are taken of: the maximum, or nothing along an
+    /// unbounded axis.
+    pub fn available(&self) -> (f32, f32) {
+        let finite = |max: f32| if max.is_finite() { max } else { 0.0 };
+        (finite(self.max_width), finite(self.max_height))
+    }
+}
+
+/// The outcome of the arrange pass: where a widget and, recursively, each of
+/// its children ended up, in window coordinates.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct LayoutNode {
+    pub rect: Rect,
+    pub children: Vec<LayoutNode>,
+}
+
+impl LayoutNode {
+    /// A node without children.
+    pub fn leaf(rect: Rect) -> Self {
+        Self {
+            rect,
+            children: Vec::new(),
+        }
+    }
+}
+
+/// The window that widgets are laid out in, for viewport-relative and
+/// logical sizes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Viewport {
+    pub width: f32,
+    pub height: f32,
+    /// Physical pixels per logical pixel.
+    pub scale_factor: f32,
+}
+
+impl Default for Viewport {
+    fn default() -> Self {
+        Self {
+            width: 0.0,
+            height: 0.0,
+            scale_factor: 1.0,
+        }
+    }
+}
+
+thread_local! {
+    static VIEWPORT: std::cell::Cell<Viewport> = std::cell::Cell::new(Viewport::default());
+}
+
+impl Viewport {
+    /// The viewport of the layout running on this thread, see [`with_viewport`].
+    pub fn current() -> Viewport {
+        VIEWPORT.with(|viewport| viewport.get())
+    }
+}
+
+/// Runs a layout or paint pass with `viewport` as the current viewport,
+/// restoring the previous one afterwards.
+pub fn with_viewport<R>(viewport: Viewport, pass: impl FnOnce() -> R) -> R {
+    let previous = VIEWPORT.with(|current| current.replace(viewport));
+    let result = pass();
+    VIEWPORT.with(|current| current.set(previous));
+    result
+}
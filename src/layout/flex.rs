@@ -0,0 +1,234 @@
+use crate::utils::Rect;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FlexDirection {
+    #[default]
+    Row,
+    RowReverse,
+    Column,
+    ColumnReverse,
+}
+
+impl FlexDirection {
+    pub fn is_row(&self) -> bool {
+        matches!(self, FlexDirection::Row | FlexDirection::RowReverse)
+    }
+
+    pub fn is_reverse(&self) -> bool {
+        matches!(
+            self,
+            FlexDirection::RowReverse | FlexDirection::ColumnReverse
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FlexWrap {
+    #[default]
+    NoWrap,
+    Wrap,
+}
+
+/// Distribution of the free space along the main axis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum JustifyContent {
+    #[default]
+    Start,
+    End,
+    Center,
+    SpaceBetween,
+    SpaceAround,
+    SpaceEvenly,
+}
+
+/// Placement of the items along the cross axis of their line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AlignItems {
+    Start,
+    End,
+    Center,
+    /// Fills the line, for items without a cross size of their own.
+    #[default]
+    Stretch,
+}
+
+/// The properties of a flex container.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct FlexContainer {
+    pub direction: FlexDirection,
+    pub wrap: FlexWrap,
+    pub justify_content: JustifyContent,
+    pub align_items: AlignItems,
+    /// Space between neighbouring items, and between wrapped lines.
+    pub gap: f32,
+}
+
+/// One item of a flex container, with every size resolved to pixels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FlexChild {
+    /// The main size before growing or shrinking.
+    pub basis: f32,
+    /// The cross size, `None` when the item takes the size of its line if
+    /// stretched and of its content otherwise.
+    pub cross: Option<f32>,
+    /// The cross size of the content, for items without a cross size.
+    pub content_cross: f32,
+    pub grow: f32,
+    pub shrink: f32,
+    pub align_self: Option<AlignItems>,
+}
+
+impl Default for FlexChild {
+    fn default() -> Self {
+        Self {
+            basis: 0.0,
+            cross: None,
+            content_cross: 0.0,
+            grow: 0.0,
+            shrink: 1.0,
+            align_self: None,
+        }
+    }
+}
+
+/// Lays out `children` in a container of `width` by `height` pixels and
+/// returns their rectangles, relative to the container, in the same order.
+pub fn flex(
+    container: &FlexContainer,
+    width: f32,
+    height: f32,
+    children: &[FlexChild],
+) -> Vec<Rect> {
+    let row = container.direction.is_row();
+    let (main_size, cross_size) = if row {
+        (width, height)
+    } else {
+        (height, width)
+    };
+    let gap = container.gap;
+
+    let lines = break_lines(container, main_size, children);
+    let single_line = lines.len() <= 1 && container.wrap == FlexWrap::NoWrap;
+
+    // (main offset, main size, cross offset, cross size) for each child.
+    let mut placed = vec![(0.0, 0.0, 0.0, 0.0); children.len()];
+    let mut line_cross_start = 0.0;
+
+    for line in lines {
+        let items = &children[line.clone()];
+        let sizes = resolve_main_sizes(items, main_size, gap);
+
+        let line_cross = if single_line {
+            cross_size
+        } else {
+            items
+                .iter()
+                .map(|item| item.cross.unwrap_or(item.content_cross))
+                .fold(0.0, f32::max)
+        };
+
+        let used: f32 = sizes.iter().sum::<f32>() + gap * (items.len() as f32 - 1.0).max(0.0);
+        let free = (main_size - used).max(0.0);
+        let count = items.len() as f32;
+        let (mut cursor, spacing) = match container.justify_content {
+            JustifyContent::Start => (0.0, 0.0),
+            JustifyContent::End => (free, 0.0),
+            JustifyContent::Center => (free / 2.0, 0.0),
+            JustifyContent::SpaceBetween if count > 1.0 => (0.0, free / (count - 1.0)),
+            JustifyContent::SpaceBetween => (0.0, 0.0),
+            JustifyContent::SpaceAround => (free / count / 2.0, free / count),
+            JustifyContent::SpaceEvenly => (free / (count + 1.0), free / (count + 1.0)),
+        };
+
+        for ((index, item), size) in line.clone().zip(items).zip(sizes) {
+            let align = item.align_self.unwrap_or(container.align_items);
+            let cross = match (align, item.cross) {
+                (AlignItems::Stretch, None) => line_cross,
+                (_, cross) => cross.unwrap_or(item.content_cross),
+            };
+            let cross_offset = match align {
+                AlignItems::Start | AlignItems::Stretch => 0.0,
+                AlignItems::End => line_cross - cross,
+                AlignItems::Center => (line_cross - cross) / 2.0,
+            };
+            placed[index] = (cursor, size, line_cross_start + cross_offset, cross);
+            cursor += size + gap + spacing;
+        }
+
+        line_cross_start += line_cross + gap;
+    }
+
+    placed
+        .into_iter()
+        .map(|(main, main_len, cross, cross_len)| {
+            let main = if container.direction.is_reverse() {
+                main_size - main - main_len
+            } else {
+                main
+            };
+            if row {
+                Rect::new(main, cross, main_len, cross_len)
+            } else {
+                Rect::new(cross, main, cross_len, main_len)
+            }
+        })
+        .collect()
+}
+
+/// Splits the children into lines that each fit the main size, keeping at
+/// least one item on every line.
+fn break_lines(
+    container: &FlexContainer,
+    main_size: f32,
+    children: &[FlexChild],
+) -> Vec<std::ops::Range<usize>> {
+    if container.wrap == FlexWrap::NoWrap || children.is_empty() {
+        return std::iter::once(0..children.len()).collect();
+    }
+    let mut lines = Vec::new();
+    let mut start = 0;
+    let mut used = 0.0;
+    for (index, child) in children.iter().enumerate() {
+        let needed = if index == start {
+            child.basis
+        } else {
+            used + container.gap + child.basis
+        };
+        if index > start && needed > main_size {
+            lines.push(start..index);
+            start = index;
+            used = child.basis;
+        } else {
+            used = needed;
+        }
+    }
+    lines.push(start..children.len());
+    lines
+}
+
+/// Grows or shrinks the bases of one line so that they fill `main_size`.
+fn resolve_main_sizes(items: &[FlexChild], main_size: f32, gap: f32) -> Vec<f32> {
+    let gaps = gap * (items.len() as f32 - 1.0).max(0.0);
+    let bases: f32 = items.iter().map(|item| item.basis).sum();
+    let free = main_size - bases - gaps;
+
+    if free > 0.0 {
+        let grow: f32 = items.iter().map(|item| item.grow).sum();
+        if grow > 0.0 {
+            return items
+                .iter()
+                .map(|item| item.basis + free * item.grow / grow)
+                .collect();
+        }
+    } else if free < 0.0 {
+        // Shrinking is weighted by the basis, so large items give up more.
+        let shrink: f32 = items.iter().map(|item| item.shrink * item.basis).sum();
+        if shrink > 0.0 {
+            return items
+                .iter()
+                .map(|item| (item.basis + free * item.shrink * item.basis / shrink).max(0.0))
+                .collect();
+        }
+    }
+    items.iter().map(|item| item.basis).collect()
+}
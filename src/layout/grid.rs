@@ -0,0 +1,295 @@
+use crate::utils::Rect;
+
+/// The size of one grid row or column.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Track {
+    Pixel(f32),
+    /// A percentage of the container's size along the same axis.
+    Percent(f32),
+    /// A share of the space left after every other track is sized.
+    Fraction(f32),
+    /// As large as the largest item that sits in this track alone.
+    #[default]
+    Auto,
+}
+
+/// Where an item sits in the grid. Unset lines are filled in by
+/// auto-placement, in row-major order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GridPlacement {
+    /// Zero-based index of the first row.
+    pub row: Option<usize>,
+    /// Zero-based index of the first column.
+    pub column: Option<usize>,
+    pub row_span: usize,
+    pub column_span: usize,
+}
+
+impl Default for GridPlacement {
+    fn default() -> Self {
+        Self {
+            row: None,
+            column: None,
+            row_span: 1,
+            column_span: 1,
+        }
+    }
+}
+
+/// The properties of a grid container.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct GridContainer {
+    pub rows: Vec<Track>,
+    pub columns: Vec<Track>,
+    pub row_gap: f32,
+    pub column_gap: f32,
+    /// The size of rows created for items placed below the explicit ones.
+    pub auto_rows: Track,
+}
+
+/// One item of a grid container, with its own size resolved to pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct GridChild {
+    pub placement: GridPlacement,
+    pub width: f32,
+    pub height: f32,
+}
+
+/// Lays out `children` in a container of `width` by `height` pixels and
+/// returns their rectangles, relative to the container, in the same order.
+pub fn grid(
+    container: &GridContainer,
+    width: f32,
+    height: f32,
+    children: &[GridChild],
+) -> Vec<Rect> {
+    let cells = place(container, children);
+    let row_count = cells
+        .iter()
+        .map(|cell| cell.row + cell.row_span)
+        .max()
+        .unwrap_or(0)
+        .max(container.rows.len());
+    let column_count = column_count(container, children);
+
+    let rows: Vec<Track> = (0..row_count)
+        .map(|i| {
+            container
+                .rows
+                .get(i)
+                .copied()
+                .unwrap_or(container.auto_rows)
+        })
+        .collect();
+    let columns: Vec<Track> = (0..column_count)
+        .map(|i| container.columns.get(i).copied().unwrap_or(Track::Auto))
+        .collect();
+
+    let row_spans: Vec<_> = cells
+        .iter()
+        .zip(children)
+        .map(|(cell, child)| (cell.row, cell.row_span, child.height))
+        .collect();
+    let column_spans: Vec<_> = cells
+        .iter()
+        .zip(children)
+        .map(|(cell, child)| (cell.column, cell.column_span, child.width))
+        .collect();
+
+    let row_sizes = size_tracks(&rows, height, container.row_gap, &row_spans);
+    let column_sizes = size_tracks(&columns, width, container.column_gap, &column_spans);
+    let row_starts = starts(&row_sizes, container.row_gap);
+    let column_starts = starts(&column_sizes, container.column_gap);
+
+    cells
+        .iter()
+        .map(|cell| {
+            let span = |starts: &[f32], sizes: &[f32], first: usize, count: usize, gap: f32| {
+                let sizes = &sizes[first..first + count];
+                let length = sizes.iter().sum::<f32>() + gap * (count as f32 - 1.0);
+                (starts[first], length)
+            };
+            let (x, width) = span(
+                &column_starts,
+                &column_sizes,
+                cell.column,
+                cell.column_span,
+                container.column_gap,
+            );
+            let (y, height) = span(
+                &row_starts,
+                &row_sizes,
+                cell.row,
+                cell.row_span,
+                container.row_gap,
+            );
+            Rect::new(x, y, width, height)
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Cell {
+    row: usize,
+    column: usize,
+    row_span: usize,
+    column_span: usize,
+}
+
+fn column_count(container: &GridContainer, children: &[GridChild]) -> usize {
+    children
+        .iter()
+        .filter_map(|child| {
+            let placement = child.placement;
+            placement
+                .column
+                .map(|column| column + placement.column_span.max(1))
+        })
+        .chain(std::iter::once(container.columns.len()))
+        .max()
+        .unwrap_or(0)
+        .max(1)
+}
+
+/// Assigns every child a cell: fully explicit items first, then items with
+/// one fixed line, then the rest in row-major order.
+fn place(container: &GridContainer, children: &[GridChild]) -> Vec<Cell> {
+    let columns = column_count(container, children);
+    let mut occupied: Vec<Vec<bool>> = Vec::new();
+    let mut cells = vec![None; children.len()];
+
+    let fits = |occupied: &Vec<Vec<bool>>, cell: &Cell| {
+        cell.column + cell.column_span <= columns
+            && (cell.row..cell.row + cell.row_span).all(|row| {
+                (cell.column..cell.column + cell.column_span).all(|column| {
+                    !occupied
+                        .get(row)
+                        .is_some_and(|cells: &Vec<bool>| cells[column])
+                })
+            })
+    };
+    let occupy = |occupied: &mut Vec<Vec<bool>>, cell: &Cell| {
+        while occupied.len() < cell.row + cell.row_span {
+            occupied.push(vec![false; columns]);
+        }
+        for row in &mut occupied[cell.row..cell.row + cell.row_span] {
+            for column in &mut row[cell.column..cell.column + cell.column_span] {
+                *column = true;
+            }
+        }
+    };
+
+    let cell_of = |child: &GridChild, row: usize, column: usize| Cell {
+        row,
+        column,
+        row_span: child.placement.row_span.max(1),
+        column_span: child.placement.column_span.max(1).min(columns),
+    };
+
+    // Items placed on both axes may overlap, exactly as requested.
+    for (index, child) in children.iter().enumerate() {
+        if let (Some(row), Some(column)) = (child.placement.row, child.placement.column) {
+            let cell = cell_of(child, row, column);
+            occupy(&mut occupied, &cell);
+            cells[index] = Some(cell);
+        }
+    }
+
+    for (index, child) in children.iter().enumerate() {
+        let cell = match (child.placement.row, child.placement.column) {
+            (Some(row), None) => (0..columns)
+                .map(|column| cell_of(child, row, column))
+                .find(|cell| fits(&occupied, cell))
+                .unwrap_or_else(|| cell_of(child, row, 0)),
+            (None, Some(column)) => (0..)
+                .map(|row| cell_of(child, row, column))
+                .find(|cell| fits(&occupied, cell))
+                .expect("rows are unbounded"),
+            _ => continue,
+        };
+        occupy(&mut occupied, &cell);
+        cells[index] = Some(cell);
+    }
+
+    let mut cursor = (0, 0);
+    for (index, child) in children.iter().enumerate() {
+        if cells[index].is_some() {
+            continue;
+        }
+        let (mut row, mut column) = cursor;
+        let cell = loop {
+            let cell = cell_of(child, row, column);
+            if fits(&occupied, &cell) {
+                break cell;
+            }
+            column += 1;
+            if column >= columns {
+                column = 0;
+                row += 1;
+            }
+        };
+        occupy(&mut occupied, &cell);
+        cursor = (cell.row, cell.column + cell.column_span);
+        cells[index] = Some(cell);
+    }
+
+    cells
+        .into_iter()
+        .map(|cell| cell.expect("every child is placed"))
+        .collect()
+}
+
+/// Sizes the tracks of one axis. `items` holds the first track, span and
+/// size of every child along that axis.
+fn size_tracks(
+    tracks: &[Track],
+    available: f32,
+    gap: f32,
+    items: &[(usize, usize, f32)],
+) -> Vec<f32> {
+    let mut sizes: Vec<f32> = tracks
+        .iter()
+        .enumerate()
+        .map(|(index, track)| match track {
+            Track::Pixel(pixels) => *pixels,
+            Track::Percent(percent) => available * percent / 100.0,
+            Track::Fraction(_) => 0.0,
+            // Only items spanning a single track contribute to its size.
+            Track::Auto => items
+                .iter()
+                .filter(|(first, span, _)| *first == index && *span == 1)
+                .map(|(_, _, size)| *size)
+                .fold(0.0, f32::max),
+        })
+        .collect();
+
+    let fractions: f32 = tracks
+        .iter()
+        .map(|track| match track {
+            Track::Fraction(fraction) => *fraction,
+            _ => 0.0,
+        })
+        .sum();
+    if fractions > 0.0 {
+        let gaps = gap * (tracks.len() as f32 - 1.0).max(0.0);
+        let free = (available - sizes.iter().sum::<f32>() - gaps).max(0.0);
+        for (size, track) in sizes.iter_mut().zip(tracks) {
+            if let Track::Fraction(fraction) = track {
+                *size = free * fraction / fractions;
+            }
+        }
+    }
+    sizes
+}
+
+fn starts(sizes: &[f32], gap: f32) -> Vec<f32> {
+    let mut position = 0.0;
+    sizes
+        .iter()
+        .map(|size| {
+            let start = position;
+            position += size + gap;
+            start
+        })
+        .collect()
+}
@@ -0,0 +1,8 @@
+pub mod constraints;
+pub mod flex;
+pub mod grid;
+pub mod stack;
+pub use constraints::*;
+pub use flex::*;
+pub use grid::*;
+pub use stack::*;
@@ -0,0 +1,90 @@
+use crate::utils::Rect;
+
+/// A point of the container that a stacked item lines up with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Anchor {
+    #[default]
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+impl Anchor {
+    /// The anchor as fractions of the width and height, from the top-left.
+    pub fn fractions(&self) -> (f32, f32) {
+        match self {
+            Anchor::TopLeft => (0.0, 0.0),
+            Anchor::Top => (0.5, 0.0),
+            Anchor::TopRight => (1.0, 0.0),
+            Anchor::Left => (0.0, 0.5),
+            Anchor::Center => (0.5, 0.5),
+            Anchor::Right => (1.0, 0.5),
+            Anchor::BottomLeft => (0.0, 1.0),
+            Anchor::Bottom => (0.5, 1.0),
+            Anchor::BottomRight => (1.0, 1.0),
+        }
+    }
+}
+
+/// How an item is positioned within a stack.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct StackPlacement {
+    /// Overrides the container's alignment for this item.
+    pub anchor: Option<Anchor>,
+    /// Moves the item after it is aligned, in pixels.
+    pub offset_x: f32,
+    pub offset_y: f32,
+    /// Items with a higher z-index are drawn above the others, ties keep the
+    /// order of the children.
+    pub z_index: i32,
+}
+
+/// One item of a stack container, with its size resolved to pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct StackChild {
+    pub placement: StackPlacement,
+    pub width: f32,
+    pub height: f32,
+}
+
+/// The properties of a stack container.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct StackContainer {
+    pub alignment: Anchor,
+}
+
+/// Lays out `children` on top of each other in a container of `width` by
+/// `height` pixels, and returns their rectangles in the same order.
+pub fn stack(
+    container: &StackContainer,
+    width: f32,
+    height: f32,
+    children: &[StackChild],
+) -> Vec<Rect> {
+    children
+        .iter()
+        .map(|child| {
+            let anchor = child.placement.anchor.unwrap_or(container.alignment);
+            let (fx, fy) = anchor.fractions();
+            Rect::new(
+                (width - child.width) * fx + child.placement.offset_x,
+                (height - child.height) * fy + child.placement.offset_y,
+                child.width,
+                child.height,
+            )
+        })
+        .collect()
+}
+
+/// The indices of `children` from the bottom-most to the top-most.
+pub fn paint_order(children: &[StackChild]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..children.len()).collect();
+    order.sort_by_key(|&index| children[index].placement.z_index);
+    order
+}
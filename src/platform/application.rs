@@ -0,0 +1,732 @@
+use std::{
+    any::Any,
+    cell::{Cell, RefCell},
+    collections::VecDeque,
+    path::{Path, PathBuf},
+    rc::Rc,
+    sync::{mpsc, Arc, Mutex, PoisonError},
+    time::{Duration, SystemTime},
+};
+
+use winit::event_loop::{EventLoop, EventLoopProxy, EventLoopWindowTarget};
+
+use super::{
+    new_event_loop, run_event_loop, HeadlessWindow, InputState, WindowInterface, WinitWindow,
+};
+use crate::event::{window::WindowEvent, Event};
+use crate::renderer::{Renderer, WgpuRenderer};
+use crate::style::{Stylesheet, StylesheetError, Theme};
+use crate::widget;
+
+/// Identifies a window opened by an [`Application`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct WindowId(u64);
+
+/// How a window relates to the other windows of an [`Application`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WindowKind {
+    #[default]
+    Normal,
+    /// Belongs to the given window and is closed along with it.
+    Child(WindowId),
+    /// A child window that blocks pointer and keyboard input to its parent
+    /// until it is closed, kept above its parent.
+    Modal(WindowId),
+    /// A small child window, such as a palette, kept above its parent.
+    ///
+    /// Only Windows lets a window be owned by another, which keeps it above
+    /// its owner. Elsewhere modal and tool windows are ordinary windows that
+    /// the parent can be raised above.
+    Tool(WindowId),
+}
+
+impl WindowKind {
+    pub fn parent(&self) -> Option<WindowId> {
+        match *self {
+            WindowKind::Normal => None,
+            WindowKind::Child(parent) | WindowKind::Modal(parent) | WindowKind::Tool(parent) => {
+                Some(parent)
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+enum Request {
+    Open(WindowId, Box<widget::Window>, WindowKind),
+    Close(WindowId),
+    Exit(i32),
+    Theme(Box<Theme>),
+}
+
+/// Opens and closes the windows of an [`Application`] from event handlers.
+/// Requests take effect once the event being handled is done.
+#[derive(Debug, Clone, Default)]
+pub struct AppHandle {
+    next_id: Rc<Cell<u64>>,
+    requests: Rc<RefCell<VecDeque<Request>>>,
+}
+
+impl AppHandle {
+    pub fn open(&self, window: widget::Window) -> WindowId {
+        self.open_with(window, WindowKind::Normal)
+    }
+
+    /// Opens a window of the given kind once the request is handled. A window
+    /// that cannot be opened by then, such as the child of a window closed in
+    /// the meantime, is logged and its id never names an open window, see
+    /// [`Application::windows`].
+    pub fn open_with(&self, window: widget::Window, kind: WindowKind) -> WindowId {
+        let id = self.next_id();
+        self.request(Request::Open(id, Box::new(window), kind));
+        id
+    }
+
+    /// Closes a window along with its child, modal and tool windows.
+    pub fn close(&self, id: WindowId) {
+        self.request(Request::Close(id));
+    }
+
+    /// Closes every window without asking and ends the application, which
+    /// returns `code` from [`Application::run`].
+    pub fn exit(&self, code: i32) {
+        self.request(Request::Exit(code));
+    }
+
+    /// Switches every window to `theme`, see [`Application::set_theme`].
+    pub fn set_theme(&self, theme: Theme) {
+        self.request(Request::Theme(Box::new(theme)));
+    }
+
+    fn next_id(&self) -> WindowId {
+        let id = WindowId(self.next_id.get());
+        self.next_id.set(id.0 + 1);
+        id
+    }
+
+    fn request(&self, request: Request) {
+        self.requests.borrow_mut().push_back(request);
+    }
+
+    fn next_request(&self) -> Option<Request> {
+        self.requests.borrow_mut().pop_front()
+    }
+}
+
+/// What an [`AppProxy`] sends to the UI thread.
+enum UserEvent {
+    Message(Box<dyn Any + Send>),
+    Redraw(WindowId),
+    /// The file of the watch at this index changed.
+    FileChanged(usize),
+}
+
+/// What a watched file is loaded into.
+enum Target {
+    Stylesheet(WindowId),
+    Theme,
+}
+
+/// A file reloaded whenever it changes on disk.
+struct Watch {
+    path: PathBuf,
+    target: Target,
+}
+
+enum Sender {
+    Native(EventLoopProxy<UserEvent>),
+    Headless(mpsc::Sender<UserEvent>),
+}
+
+/// Posts messages and redraw requests to an [`Application`] from any thread,
+/// waking up its event loop.
+#[derive(Clone)]
+pub struct AppProxy {
+    sender: Arc<Mutex<Sender>>,
+}
+
+impl AppProxy {
+    /// Sends `message` to the [`on_message`](Application::on_message)
+    /// listeners, on the thread running the application.
+    pub fn post<M: Any + Send>(&self, message: M) -> Result<(), ApplicationClosed> {
+        self.send(UserEvent::Message(Box::new(message)))
+    }
+
+    /// Paints the window again, for example after a background task changed
+    /// what its widgets show.
+    pub fn request_redraw(&self, window: WindowId) -> Result<(), ApplicationClosed> {
+        self.send(UserEvent::Redraw(window))
+    }
+
+    fn send(&self, event: UserEvent) -> Result<(), ApplicationClosed> {
+        match &*self.sender.lock().unwrap_or_else(PoisonError::into_inner) {
+            Sender::Native(proxy) => proxy.send_event(event).map_err(|_| ApplicationClosed),
+            Sender::Headless(sender) => sender.send(event).map_err(|_| ApplicationClosed),
+        }
+    }
+}
+
+impl std::fmt::Debug for AppProxy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AppProxy").finish_non_exhaustive()
+    }
+}
+
+/// The application an [`AppProxy`] posts to has ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ApplicationClosed;
+
+impl std::fmt::Display for ApplicationClosed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "the application is no longer running")
+    }
+}
+
+impl std::error::Error for ApplicationClosed {}
+
+/// A message posted through an [`AppProxy`].
+#[derive(Clone)]
+pub struct Message(Rc<dyn Any + Send>);
+
+impl Message {
+    /// The message, if it is an `M`.
+    pub fn get<M: Any>(&self) -> Option<&M> {
+        self.0.downcast_ref()
+    }
+}
+
+impl std::fmt::Debug for Message {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Message(..)")
+    }
+}
+
+enum Platform {
+    Native {
+        window: Box<WinitWindow>,
+        renderer: Box<WgpuRenderer>,
+        input: InputState,
+    },
+    Headless(HeadlessWindow),
+}
+
+impl Platform {
+    fn redraw(&mut self) {
+        match self {
+            Platform::Native { window, .. } => window.native().request_redraw(),
+            Platform::Headless(window) => window.dispatch(WindowEvent::RedrawRequested),
+        }
+    }
+
+    fn trigger(&mut self, event: WindowEvent) {
+        match self {
+            Platform::Native { window, .. } => window.trigger(event),
+            // The application decides whether a close request closes the window.
+            Platform::Headless(window) if matches!(event, WindowEvent::CloseRequested(_)) => {
+                window.trigger(event)
+            }
+            Platform::Headless(window) => window.dispatch(event),
+        }
+    }
+}
+
+struct AppWindow {
+    id: WindowId,
+    kind: WindowKind,
+    platform: Platform,
+}
+
+/// Owns the event loop shared by any number of windows, routing each native
+/// event to the window it is for. The application ends once its last
+/// window is closed, or when [`AppHandle::exit`] is called.
+pub struct Application {
+    /// Triggered once the event loop is running, before any window event.
+    pub on_start: Event<AppHandle>,
+    /// Triggered with the exit code once every window is closed, before
+    /// [`run`](Self::run) returns.
+    pub on_exit: Event<i32>,
+    /// Triggered for every message posted through an [`AppProxy`].
+    pub on_message: Event<Message>,
+    /// `None` for a headless application, and while running.
+    event_loop: Option<EventLoop<UserEvent>>,
+    handle: AppHandle,
+    proxy: AppProxy,
+    /// What the proxy of a headless application posts to.
+    posted: Option<mpsc::Receiver<UserEvent>>,
+    windows: Vec<AppWindow>,
+    exit_code: Option<i32>,
+    watches: Vec<Watch>,
+    /// Dropped along with the application, which stops the threads watching
+    /// files.
+    alive: Arc<()>,
+}
+
+impl Application {
+    /// An application with native windows.
+    ///
+    /// Panics if an event loop was created before, which winit allows once
+    /// per process. That is also why there is no `Default`.
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        let event_loop = new_event_loop();
+        let sender = Sender::Native(event_loop.create_proxy());
+        Self::with_sender(Some(event_loop), sender, None)
+    }
+
+    /// An application whose windows are [`HeadlessWindow`]s, with events
+    /// delivered through [`dispatch`](Self::dispatch).
+    pub fn headless() -> Self {
+        let (sender, receiver) = mpsc::channel();
+        Self::with_sender(None, Sender::Headless(sender), Some(receiver))
+    }
+
+    fn with_sender(
+        event_loop: Option<EventLoop<UserEvent>>,
+        sender: Sender,
+        posted: Option<mpsc::Receiver<UserEvent>>,
+    ) -> Self {
+        Self {
+            on_start: Event::new(),
+            on_exit: Event::new(),
+            on_message: Event::new(),
+            event_loop,
+            handle: AppHandle::default(),
+            proxy: AppProxy {
+                sender: Arc::new(Mutex::new(sender)),
+            },
+            posted,
+            windows: Vec::new(),
+            exit_code: None,
+            watches: Vec::new(),
+            alive: Arc::new(()),
+        }
+    }
+
+    /// A proxy for posting to this application from other threads.
+    pub fn proxy(&self) -> AppProxy {
+        self.proxy.clone()
+    }
+
+    /// A handle for opening and closing windows from event handlers.
+    pub fn handle(&self) -> AppHandle {
+        self.handle.clone()
+    }
+
+    pub fn open(&mut self, window: widget::Window) -> WindowId {
+        self.open_with(window, WindowKind::Normal)
+    }
+
+    pub fn open_with(&mut self, window: widget::Window, kind: WindowKind) -> WindowId {
+        let id = self.handle.open_with(window, kind);
+        self.process_requests();
+        id
+    }
+
+    /// Opens a window right away, failing with the reason it could not be
+    /// opened where [`open`](Self::open) logs it.
+    pub fn try_open(&mut self, window: widget::Window) -> crate::Result<WindowId> {
+        self.process_requests();
+        let platform = match self.event_loop.as_deref() {
+            Some(target) => self.create_native(window, WindowKind::Normal, target)?,
+            None => Self::create_headless(window)?,
+        };
+        let id = self.handle.next_id();
+        self.windows.push(AppWindow {
+            id,
+            kind: WindowKind::Normal,
+            platform,
+        });
+        Ok(id)
+    }
+
+    pub fn close(&mut self, id: WindowId) {
+        self.handle.close(id);
+        self.process_requests();
+    }
+
+    /// The open windows, in the order they were opened.
+    pub fn windows(&self) -> Vec<WindowId> {
+        self.windows.iter().map(|window| window.id).collect()
+    }
+
+    pub fn kind(&self, id: WindowId) -> Option<WindowKind> {
+        self.window(id).map(|window| window.kind)
+    }
+
+    /// Whether a modal window keeps `id` from receiving input.
+    pub fn is_blocked(&self, id: WindowId) -> bool {
+        self.windows
+            .iter()
+            .any(|window| window.kind == WindowKind::Modal(id))
+    }
+
+    /// The platform window of a headless application.
+    pub fn headless_window(&self, id: WindowId) -> Option<&HeadlessWindow> {
+        match &self.window(id)?.platform {
+            Platform::Headless(window) => Some(window),
+            Platform::Native { .. } => None,
+        }
+    }
+
+    /// Styles the widgets of every window with `theme` from now on, and
+    /// paints them again.
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.handle.set_theme(theme);
+        self.process_requests();
+    }
+
+    /// How often watched files are checked for changes.
+    pub const WATCH_INTERVAL: Duration = Duration::from_millis(250);
+
+    /// Applies the stylesheet at `path` to a window, and again whenever the
+    /// file changes, to work on the look of an application while it runs.
+    /// A change that does not parse is logged and leaves the window as is.
+    pub fn watch_stylesheet(
+        &mut self,
+        id: WindowId,
+        path: impl Into<PathBuf>,
+    ) -> Result<(), StylesheetError> {
+        let path = path.into();
+        let stylesheet = Stylesheet::load(&path)?;
+        self.dispatch(id, WindowEvent::StylesheetChanged(stylesheet));
+        self.watch(path, Target::Stylesheet(id));
+        Ok(())
+    }
+
+    /// Switches to the theme at `path`, and to the file's new theme whenever
+    /// it changes, see [`watch_stylesheet`](Self::watch_stylesheet).
+    pub fn watch_theme(&mut self, path: impl Into<PathBuf>) -> Result<(), StylesheetError> {
+        let path = path.into();
+        self.set_theme(Theme::load(&path)?);
+        self.watch(path, Target::Theme);
+        Ok(())
+    }
+
+    /// Handles what was posted to a headless application from other threads,
+    /// such as changes to watched files. A running application does so as
+    /// soon as it is posted.
+    pub fn pump(&mut self) {
+        self.process_requests();
+    }
+
+    /// Delivers `event` to a window as if it came from the platform.
+    pub fn dispatch(&mut self, id: WindowId, event: WindowEvent) {
+        self.deliver(id, event);
+        self.process_requests();
+    }
+
+    /// Runs the event loop until the last window is closed and returns the
+    /// exit code. A headless application only handles what was requested
+    /// from its start hook before it ends.
+    ///
+    /// On the web and iOS, whose event loops never return, this does not
+    /// return either unless the application is headless.
+    pub fn run(mut self) -> i32 {
+        let Some(event_loop) = self.event_loop.take() else {
+            self.on_start.trigger(self.handle());
+            self.process_requests();
+            let code = self.exit_code.unwrap_or(0);
+            self.finish(code);
+            return code;
+        };
+        self.process(Some(&*event_loop));
+        run_event_loop(event_loop, move |event, target, control_flow| {
+            if let winit::event::Event::LoopDestroyed = event {
+                let code = self.exit_code.unwrap_or(0);
+                self.finish(code);
+                return;
+            }
+            self.handle_native(event);
+            self.process(Some(target));
+            if let Some(code) = self.exit_code {
+                control_flow.set_exit_with_code(code);
+            } else if self.windows.is_empty() {
+                control_flow.set_exit();
+            }
+        })
+    }
+
+    /// Closes what is still open and tells `on_exit` the application ends
+    /// with `code`.
+    fn finish(&mut self, code: i32) {
+        // Windows can be left open when the platform ends the event loop.
+        for id in self.windows() {
+            self.remove(id);
+        }
+        self.on_exit.trigger(code);
+    }
+
+    fn handle_native(&mut self, event: winit::event::Event<UserEvent>) {
+        match event {
+            winit::event::Event::UserEvent(event) => self.handle_posted(event),
+            winit::event::Event::NewEvents(winit::event::StartCause::Init) => {
+                self.on_start.trigger(self.handle());
+            }
+            winit::event::Event::WindowEvent { window_id, event } => {
+                if let Some(window) = self.native_mut(window_id) {
+                    let id = window.id;
+                    let events = match &mut window.platform {
+                        Platform::Native {
+                            window,
+                            renderer,
+                            input,
+                        } => window.translate(event, renderer, input),
+                        Platform::Headless(_) => Vec::new(),
+                    };
+                    for event in events {
+                        self.deliver(id, event);
+                    }
+                }
+            }
+            winit::event::Event::RedrawRequested(window_id) => {
+                if let Some(window) = self.native_mut(window_id) {
+                    window.platform.trigger(WindowEvent::RedrawRequested);
+                    if let Platform::Native {
+                        window, renderer, ..
+                    } = &mut window.platform
+                    {
+                        // Whatever was painted for this redraw is rendered right away.
+                        window.surface().take_dirty();
+                        renderer.clear(Theme::current().palette.background);
+                        renderer.render(&window.surface().display_list());
+                    }
+                }
+            }
+            winit::event::Event::MainEventsCleared => {
+                for window in &self.windows {
+                    if let Platform::Native { window, .. } = &window.platform {
+                        if window.surface().take_dirty() {
+                            window.native().request_redraw();
+                        }
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn window(&self, id: WindowId) -> Option<&AppWindow> {
+        self.windows.iter().find(|window| window.id == id)
+    }
+
+    fn native_mut(&mut self, id: winit::window::WindowId) -> Option<&mut AppWindow> {
+        self.windows
+            .iter_mut()
+            .find(|window| match &window.platform {
+                Platform::Native { window, .. } => window.id() == id,
+                Platform::Headless(_) => false,
+            })
+    }
+
+    fn deliver(&mut self, id: WindowId, event: WindowEvent) {
+        match event {
+            WindowEvent::Pointer(..) | WindowEvent::Keyboard(..) if self.is_blocked(id) => {}
+            WindowEvent::CloseRequested(request) => {
+                if let Some(window) = self.windows.iter_mut().find(|window| window.id == id) {
+                    window
+                        .platform
+                        .trigger(WindowEvent::CloseRequested(request.clone()));
+                }
+                if !request.is_cancelled() {
+                    self.remove(id);
+                }
+            }
+            WindowEvent::WindowClosed => self.remove(id),
+            event => {
+                if let Some(window) = self.windows.iter_mut().find(|window| window.id == id) {
+                    window.platform.trigger(event);
+                }
+            }
+        }
+    }
+
+    fn handle_posted(&mut self, event: UserEvent) {
+        match event {
+            UserEvent::Message(message) => {
+                self.on_message.trigger(Message(Rc::from(message)));
+            }
+            UserEvent::Redraw(id) => {
+                if let Some(window) = self.windows.iter_mut().find(|window| window.id == id) {
+                    window.platform.redraw();
+                }
+            }
+            UserEvent::FileChanged(index) => self.reload(index),
+        }
+    }
+
+    /// Checks the file at `path` for changes on another thread.
+    fn watch(&mut self, path: PathBuf, target: Target) {
+        fn version(path: &Path) -> Option<(SystemTime, u64)> {
+            let metadata = std::fs::metadata(path).ok()?;
+            Some((metadata.modified().ok()?, metadata.len()))
+        }
+        let index = self.watches.len();
+        let (proxy, alive) = (self.proxy.clone(), Arc::downgrade(&self.alive));
+        let (watched, mut last) = (path.clone(), version(&path));
+        std::thread::spawn(move || {
+            while alive.upgrade().is_some() {
+                std::thread::sleep(Self::WATCH_INTERVAL);
+                let current = version(&watched);
+                if current != last {
+                    last = current;
+                    if proxy.send(UserEvent::FileChanged(index)).is_err() {
+                        return;
+                    }
+                }
+            }
+        });
+        self.watches.push(Watch { path, target });
+    }
+
+    fn reload(&mut self, index: usize) {
+        let watch = &self.watches[index];
+        let path = watch.path.clone();
+        let loaded = match watch.target {
+            Target::Stylesheet(id) => Stylesheet::load(&path)
+                .map(|stylesheet| self.deliver(id, WindowEvent::StylesheetChanged(stylesheet))),
+            Target::Theme => Theme::load(&path).map(|theme| self.handle.set_theme(theme)),
+        };
+        if let Err(error) = loaded {
+            log::error!("could not reload {}: {}", path.display(), error);
+        }
+    }
+
+    /// Handles what was posted and the queued requests outside of the event
+    /// loop.
+    fn process_requests(&mut self) {
+        if let Some(posted) = self.posted.take() {
+            for event in posted.try_iter() {
+                self.handle_posted(event);
+            }
+            self.posted = Some(posted);
+        }
+        let event_loop = self.event_loop.take();
+        self.process(event_loop.as_deref());
+        self.event_loop = event_loop;
+    }
+
+    /// Handles the queued requests, creating native windows on `target` or
+    /// headless ones without it.
+    fn process(&mut self, target: Option<&EventLoopWindowTarget<UserEvent>>) {
+        while let Some(request) = self.handle.next_request() {
+            match request {
+                Request::Open(id, window, kind) => {
+                    if let Some(platform) = self.create(id, *window, kind, target) {
+                        self.windows.push(AppWindow { id, kind, platform });
+                    }
+                }
+                Request::Close(id) => self.remove(id),
+                Request::Theme(theme) => {
+                    Theme::set(*theme);
+                    for window in &mut self.windows {
+                        window.platform.redraw();
+                    }
+                }
+                Request::Exit(code) => {
+                    self.exit_code = Some(code);
+                    for id in self.windows() {
+                        self.remove(id);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Creates the window `id` of an open request, or logs why it could not
+    /// be opened.
+    fn create(
+        &self,
+        id: WindowId,
+        window: widget::Window,
+        kind: WindowKind,
+        target: Option<&EventLoopWindowTarget<UserEvent>>,
+    ) -> Option<Platform> {
+        if let Some(parent) = kind
+            .parent()
+            .filter(|&parent| self.window(parent).is_none())
+        {
+            log::warn!("{:?} was not opened, its parent {:?} is closed", id, parent);
+            return None;
+        }
+        let platform = match target {
+            Some(target) => self.create_native(window, kind, target),
+            None => Self::create_headless(window),
+        };
+        platform
+            .map_err(|error| log::error!("{:?} could not be opened: {}", id, error))
+            .ok()
+    }
+
+    fn create_headless(window: widget::Window) -> crate::Result<Platform> {
+        let mut headless = HeadlessWindow::new(window.title);
+        window.attach(&mut headless)?;
+        headless.open();
+        Ok(Platform::Headless(headless))
+    }
+
+    fn create_native(
+        &self,
+        window: widget::Window,
+        kind: WindowKind,
+        target: &EventLoopWindowTarget<UserEvent>,
+    ) -> crate::Result<Platform> {
+        let mut builder = winit::window::WindowBuilder::new()
+            .with_title(window.title)
+            .with_visible(false);
+        if let WindowKind::Modal(parent) | WindowKind::Tool(parent) = kind {
+            if let Some(Platform::Native { window, .. }) =
+                self.window(parent).map(|window| &window.platform)
+            {
+                builder = with_owner(builder, &window.native());
+            }
+        }
+        let native = builder.build(target)?;
+        let mut native = WinitWindow::new(native);
+        let renderer = pollster::block_on(WgpuRenderer::new(native.native()))?;
+        window.attach(&mut native)?;
+        native.trigger(WindowEvent::WindowOpened);
+        native.native().set_visible(true);
+        Ok(Platform::Native {
+            window: Box::new(native),
+            renderer: Box::new(renderer),
+            input: InputState::default(),
+        })
+    }
+
+    /// Closes a window after its child windows, telling each it is closed.
+    fn remove(&mut self, id: WindowId) {
+        let children: Vec<_> = self
+            .windows
+            .iter()
+            .filter(|window| window.kind.parent() == Some(id))
+            .map(|window| window.id)
+            .collect();
+        for child in children {
+            self.remove(child);
+        }
+        if let Some(index) = self.windows.iter().position(|window| window.id == id) {
+            let mut window = self.windows.remove(index);
+            window.platform.trigger(WindowEvent::WindowClosed);
+        }
+    }
+}
+
+/// Keeps the window built by `builder` above `owner`.
+#[cfg(target_os = "windows")]
+fn with_owner(
+    builder: winit::window::WindowBuilder,
+    owner: &winit::window::Window,
+) -> winit::window::WindowBuilder {
+    use winit::platform::windows::{WindowBuilderExtWindows, WindowExtWindows};
+    builder.with_owner_window(owner.hwnd())
+}
+
+// winit can only embed a window in another elsewhere, which confines it to
+// the other's client area.
+#[cfg(not(target_os = "windows"))]
+fn with_owner(
+    builder: winit::window::WindowBuilder,
+    _owner: &winit::window::Window,
+) -> winit::window::WindowBuilder {
+    builder
+}
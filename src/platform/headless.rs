@@ -0,0 +1,249 @@
+use std::{cell::RefCell, collections::VecDeque, rc::Rc};
+
+use crate::event::{window::WindowEvent, Event};
+use crate::layout::Viewport;
+use crate::renderer::DisplayList;
+
+use super::{Surface, WindowInterface};
+
+/// A window that never touches the native windowing system.
+///
+/// All state lives in memory and events are injected by hand, which lets the
+/// widget tree run in tests and on servers without a display.
+pub struct HeadlessWindow {
+    title: String,
+    width: f32,
+    height: f32,
+    posx: f32,
+    posy: f32,
+    resizeable: bool,
+    fullscreen: bool,
+    decorations: bool,
+    min_size: Option<(f32, f32)>,
+    max_size: Option<(f32, f32)>,
+    visible: bool,
+    closed: bool,
+    screen: Viewport,
+
+    events: Rc<RefCell<Event<WindowEvent>>>,
+    surface: Surface,
+    pending: RefCell<VecDeque<WindowEvent>>,
+}
+
+impl HeadlessWindow {
+    pub fn new(title: &str) -> Self {
+        Self {
+            title: title.to_string(),
+            width: 0.,
+            height: 0.,
+            posx: 0.,
+            posy: 0.,
+            resizeable: true,
+            fullscreen: false,
+            decorations: true,
+            min_size: None,
+            max_size: None,
+            visible: false,
+            closed: false,
+            screen: Viewport {
+                width: 1920.,
+                height: 1080.,
+                scale_factor: 1.,
+            },
+            events: Rc::new(RefCell::new(Event::new())),
+            surface: Surface::new(),
+            pending: RefCell::new(VecDeque::new()),
+        }
+    }
+
+    /// Queues an event to be delivered once the window is shown.
+    pub fn inject(&self, event: WindowEvent) {
+        self.pending.borrow_mut().push_back(event);
+    }
+
+    /// Delivers an event to the subscribers right away, updating the
+    /// in-memory state the same way a native window would. A close request
+    /// that no subscriber cancels closes the window.
+    pub fn dispatch(&mut self, event: WindowEvent) {
+        if self.closed {
+            return;
+        }
+        match &event {
+            WindowEvent::WindowResize(width, height) => {
+                self.width = *width;
+                self.height = *height;
+            }
+            WindowEvent::WindowMoved(x, y) => {
+                self.posx = *x;
+                self.posy = *y;
+            }
+            WindowEvent::ScaleFactorChanged(scale_factor) => {
+                self.screen.scale_factor = *scale_factor;
+            }
+            WindowEvent::WindowClosed => {
+                self.visible = false;
+                self.closed = true;
+            }
+            _ => {}
+        }
+        self.trigger(event.clone());
+        if let WindowEvent::CloseRequested(request) = event {
+            if !request.is_cancelled() {
+                self.dispatch(WindowEvent::WindowClosed);
+            }
+        }
+    }
+
+    /// Sends `event` to the subscribers without any other effect.
+    pub(crate) fn trigger(&self, event: WindowEvent) {
+        self.events.borrow().trigger(event);
+    }
+
+    /// Whether the window was closed, after which events are ignored.
+    pub fn closed(&self) -> bool {
+        self.closed
+    }
+
+    /// Delivers every queued event, in the order it was injected.
+    pub fn pump(&mut self) {
+        loop {
+            let next = self.pending.borrow_mut().pop_front();
+            match next {
+                Some(event) => self.dispatch(event),
+                None => break,
+            }
+        }
+    }
+
+    /// Opens the window and delivers the injected events, like
+    /// [`show`](WindowInterface::show) without giving up the window.
+    pub fn open(&mut self) {
+        self.visible = true;
+        self.dispatch(WindowEvent::WindowOpened);
+        self.pump();
+    }
+
+    /// Pretends the window is on a screen of the given size and scale factor,
+    /// 1920x1080 at a scale of 1 unless changed.
+    pub fn set_screen(&mut self, screen: Viewport) {
+        self.screen = screen;
+    }
+
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    pub fn size(&self) -> (f32, f32) {
+        (self.width, self.height)
+    }
+
+    pub fn pos(&self) -> (f32, f32) {
+        (self.posx, self.posy)
+    }
+
+    pub fn resizeable(&self) -> bool {
+        self.resizeable
+    }
+
+    pub fn fullscreen(&self) -> bool {
+        self.fullscreen
+    }
+
+    pub fn decorations(&self) -> bool {
+        self.decorations
+    }
+
+    pub fn min_size(&self) -> Option<(f32, f32)> {
+        self.min_size
+    }
+
+    pub fn max_size(&self) -> Option<(f32, f32)> {
+        self.max_size
+    }
+
+    pub fn visible(&self) -> bool {
+        self.visible
+    }
+
+    /// What the window was last asked to draw, e.g. to hand to a
+    /// [`SoftwareRenderer`](crate::renderer::SoftwareRenderer).
+    pub fn display_list(&self) -> std::cell::Ref<'_, DisplayList> {
+        self.surface.display_list()
+    }
+}
+
+impl WindowInterface for HeadlessWindow {
+    /// Unlike a native window this does not block: it opens the window,
+    /// drains the injected events and returns.
+    fn show(mut self) {
+        self.open();
+    }
+    fn hide(&mut self) {
+        self.visible = false;
+    }
+    fn set_title(&mut self, title: &str) {
+        self.title = title.to_string();
+    }
+    fn get_title(&self) -> String {
+        self.title.clone()
+    }
+    /// Like a native window, the size is kept within the minimum and
+    /// maximum sizes.
+    fn set_size(&mut self, width: f32, height: f32) {
+        let (min_width, min_height) = self.min_size.unwrap_or((0.0, 0.0));
+        let (max_width, max_height) = self.max_size.unwrap_or((f32::INFINITY, f32::INFINITY));
+        self.width = width.min(max_width).max(min_width);
+        self.height = height.min(max_height).max(min_height);
+    }
+    fn get_size(&self) -> (f32, f32) {
+        self.size()
+    }
+    fn set_pos(&mut self, x: f32, y: f32) {
+        self.posx = x;
+        self.posy = y;
+    }
+    fn get_pos(&self) -> (f32, f32) {
+        self.pos()
+    }
+    fn set_resizeable(&mut self, resizeable: bool) {
+        self.resizeable = resizeable;
+    }
+    fn is_resizeable(&self) -> bool {
+        self.resizeable
+    }
+    fn set_fullscreen(&mut self, fullscreen: bool) {
+        self.fullscreen = fullscreen;
+    }
+    fn is_fullscreen(&self) -> bool {
+        self.fullscreen
+    }
+    fn set_min_size(&mut self, size: Option<(f32, f32)>) {
+        self.min_size = size;
+        self.set_size(self.width, self.height);
+    }
+    fn set_max_size(&mut self, size: Option<(f32, f32)>) {
+        self.max_size = size;
+        self.set_size(self.width, self.height);
+    }
+    fn set_decorations(&mut self, decorations: bool) {
+        self.decorations = decorations;
+    }
+    fn has_decorations(&self) -> bool {
+        self.decorations
+    }
+    fn handle_events<'a, F>(&self, fun: F)
+    where
+        F: Fn(WindowEvent) + 'a + 'static,
+    {
+        self.events.borrow().subscribe(fun).detach();
+    }
+    fn close(&mut self) {
+        self.dispatch(WindowEvent::WindowClosed);
+    }
+    fn surface(&self) -> Surface {
+        self.surface.clone()
+    }
+    fn screen(&self) -> Viewport {
+        self.screen
+    }
+}
@@ -1,2 +1,8 @@
+pub mod application;
+pub mod headless;
 pub mod platform;
+pub mod surface;
+pub use application::*;
+pub use headless::*;
 pub use platform::*;
+pub use surface::*;
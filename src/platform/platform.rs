@@ -1,118 +1,549 @@
-use std::sync::{Arc, Mutex};
+use std::{cell::RefCell, rc::Rc, sync::Arc};
 
-use crate::event::{window::WindowEvent, Event};
-use winit::{
-    event_loop,
-    platform::{self, windows::EventLoopBuilderExtWindows},
-    window,
+use super::{HeadlessWindow, Surface};
+use crate::event::{
+    window::{CloseRequest, ColorScheme, WindowEvent},
+    Event, Key, KeyEventKind, Modifiers, PointerButton, PointerEventKind,
 };
+use crate::layout::Viewport;
+use crate::renderer::{DisplayList, Renderer, WgpuRenderer};
+use crate::style::Theme;
+use winit::event_loop;
 
 pub trait WindowInterface {
     fn show(self);
-    fn hide(&self);
-    fn set_title(&self);
+    fn hide(&mut self);
+    fn set_title(&mut self, title: &str);
+    fn get_title(&self) -> String;
+    /// Sets the size of the window's content area, in physical pixels.
     fn set_size(&mut self, width: f32, height: f32);
-    fn get_size(&self);
-    fn set_pos(&self);
-    fn get_pos(&self);
-    fn set_resizeable(&self);
-    fn is_resizeable(&self);
-    fn set_fullscreen(&self);
+    fn get_size(&self) -> (f32, f32);
+    /// Moves the window's top-left corner to the given screen position.
+    fn set_pos(&mut self, x: f32, y: f32);
+    fn get_pos(&self) -> (f32, f32);
+    fn set_resizeable(&mut self, resizeable: bool);
+    fn is_resizeable(&self) -> bool;
+    /// Switches to borderless fullscreen on the current screen, or back.
+    fn set_fullscreen(&mut self, fullscreen: bool);
+    fn is_fullscreen(&self) -> bool;
+    /// Limits how small the user can resize the window, `None` for no limit.
+    fn set_min_size(&mut self, size: Option<(f32, f32)>);
+    /// Limits how large the user can resize the window, `None` for no limit.
+    fn set_max_size(&mut self, size: Option<(f32, f32)>);
+    /// Shows or hides the title bar and borders.
+    fn set_decorations(&mut self, decorations: bool);
+    fn has_decorations(&self) -> bool;
     fn handle_events<'a, F>(&self, fun: F)
     where
         F: Fn(WindowEvent) + 'a + 'static;
-    fn close(&self);
+    fn close(&mut self);
+    /// Replaces what the window shows with the given display list.
+    fn draw(&mut self, list: DisplayList) {
+        self.surface().draw(list);
+    }
+    /// A handle to what the window shows, for repainting from event handlers.
+    fn surface(&self) -> Surface;
+    /// The size and scale factor of the screen the window is on.
+    fn screen(&self) -> Viewport;
 }
 
 pub struct WinitWindow {
-    width: f32,
-    height: f32,
-    posx: f32,
-    posy: f32,
-    resizeable: bool,
-    fullscreen: bool,
-
-    events: Arc<Mutex<Event<WindowEvent>>>,
-    _winit: winit::window::Window,
-    _winit_eventloop: event_loop::EventLoop<()>,
+    events: Rc<RefCell<Event<WindowEvent>>>,
+    surface: Surface,
+    _winit: Arc<winit::window::Window>,
+    /// `None` when the event loop belongs to an [`Application`](super::Application).
+    _winit_eventloop: Option<event_loop::EventLoop<()>>,
 }
 
-impl WindowInterface for WinitWindow {
-    fn show(self) {
-        self._winit_eventloop.run(move |event, _, _| match event {
-            winit::event::Event::WindowEvent { window_id, event } => {
-                let custom_event = self.events.lock().unwrap();
-                match event {
-                    winit::event::WindowEvent::CloseRequested => {
-                        custom_event.trigger(WindowEvent::WindowClosed)
+/// What a native window remembers between input events.
+pub(crate) struct InputState {
+    cursor: (f32, f32),
+    modifiers: Modifiers,
+    /// The last of [`WindowEvent::WindowMinimized`], `WindowMaximized` and
+    /// `WindowRestored` that was sent.
+    state: WindowEvent,
+}
+
+impl Default for InputState {
+    fn default() -> Self {
+        Self {
+            cursor: (0.0, 0.0),
+            modifiers: Modifiers::default(),
+            state: WindowEvent::WindowRestored,
+        }
+    }
+}
+
+impl WinitWindow {
+    /// Wraps a native window created on an event loop run by someone else.
+    pub(crate) fn new(winit: winit::window::Window) -> Self {
+        Self {
+            events: Rc::new(RefCell::new(Event::new())),
+            surface: Surface::new(),
+            _winit: Arc::new(winit),
+            _winit_eventloop: None,
+        }
+    }
+
+    pub(crate) fn id(&self) -> winit::window::WindowId {
+        self._winit.id()
+    }
+
+    pub(crate) fn native(&self) -> Arc<winit::window::Window> {
+        self._winit.clone()
+    }
+
+    /// Sends `event` to the subscribers of [`handle_events`](WindowInterface::handle_events).
+    pub(crate) fn trigger(&self, event: WindowEvent) {
+        self.events.borrow().trigger(event);
+    }
+
+    /// Translates one native event into the events of this crate, resizing
+    /// `renderer` along with the window.
+    pub(crate) fn translate(
+        &self,
+        event: winit::event::WindowEvent,
+        renderer: &mut WgpuRenderer,
+        input: &mut InputState,
+    ) -> Vec<WindowEvent> {
+        let pointer = |kind, (x, y)| WindowEvent::Pointer(kind, x, y);
+        match event {
+            winit::event::WindowEvent::CursorMoved { position, .. } => {
+                input.cursor = (position.x as f32, position.y as f32);
+                vec![pointer(PointerEventKind::Move, input.cursor)]
+            }
+            winit::event::WindowEvent::CursorLeft { .. } => {
+                vec![pointer(PointerEventKind::Leave, input.cursor)]
+            }
+            winit::event::WindowEvent::MouseInput { state, button, .. } => {
+                let button = pointer_button(button);
+                let kind = match state {
+                    winit::event::ElementState::Pressed => PointerEventKind::Down(button),
+                    winit::event::ElementState::Released => PointerEventKind::Up(button),
+                };
+                vec![pointer(kind, input.cursor)]
+            }
+            winit::event::WindowEvent::MouseWheel { delta, .. } => {
+                let (delta_x, delta_y) = match delta {
+                    winit::event::MouseScrollDelta::LineDelta(x, y) => {
+                        (x * LINE_SCROLL, y * LINE_SCROLL)
+                    }
+                    winit::event::MouseScrollDelta::PixelDelta(delta) => {
+                        (delta.x as f32, delta.y as f32)
                     }
-                    winit::event::WindowEvent::Resized(size) => {
-                        custom_event.trigger(WindowEvent::WindowResize(
-                            size.width as f32,
-                            size.height as f32,
-                        ));
+                };
+                let kind = PointerEventKind::Wheel { delta_x, delta_y };
+                vec![pointer(kind, input.cursor)]
+            }
+            winit::event::WindowEvent::ModifiersChanged(state) => {
+                input.modifiers = Modifiers {
+                    shift: state.shift(),
+                    control: state.ctrl(),
+                    alt: state.alt(),
+                    logo: state.logo(),
+                };
+                vec![]
+            }
+            winit::event::WindowEvent::KeyboardInput {
+                input: key_input, ..
+            } => {
+                let key = key_input.virtual_keycode.map_or(Key::Unidentified, key);
+                let kind = match key_input.state {
+                    winit::event::ElementState::Pressed => KeyEventKind::Down(key),
+                    winit::event::ElementState::Released => KeyEventKind::Up(key),
+                };
+                vec![WindowEvent::Keyboard(kind, input.modifiers)]
+            }
+            // Control characters are already reported as key presses.
+            winit::event::WindowEvent::ReceivedCharacter(character) if !character.is_control() => {
+                vec![WindowEvent::Keyboard(
+                    KeyEventKind::Text(character),
+                    input.modifiers,
+                )]
+            }
+            winit::event::WindowEvent::CloseRequested => {
+                vec![WindowEvent::CloseRequested(CloseRequest::new())]
+            }
+            winit::event::WindowEvent::Resized(size) => {
+                renderer.resize(size.width, size.height);
+                let mut events = Vec::new();
+                // Minimizing and maximizing are only reported as resizes.
+                let state = if self._winit.is_minimized() == Some(true) {
+                    WindowEvent::WindowMinimized
+                } else if self._winit.is_maximized() {
+                    WindowEvent::WindowMaximized
+                } else {
+                    WindowEvent::WindowRestored
+                };
+                if state != input.state {
+                    input.state = state.clone();
+                    events.push(state);
+                }
+                events.push(WindowEvent::WindowResize(
+                    size.width as f32,
+                    size.height as f32,
+                ));
+                events
+            }
+            winit::event::WindowEvent::ScaleFactorChanged {
+                scale_factor,
+                new_inner_size,
+            } => {
+                renderer.resize(new_inner_size.width, new_inner_size.height);
+                vec![
+                    WindowEvent::ScaleFactorChanged(scale_factor as f32),
+                    WindowEvent::WindowResize(
+                        new_inner_size.width as f32,
+                        new_inner_size.height as f32,
+                    ),
+                ]
+            }
+            winit::event::WindowEvent::Moved(position) => {
+                vec![WindowEvent::WindowMoved(
+                    position.x as f32,
+                    position.y as f32,
+                )]
+            }
+            winit::event::WindowEvent::Focused(true) => vec![WindowEvent::WindowFocused],
+            winit::event::WindowEvent::Focused(false) => vec![WindowEvent::WindowUnfocused],
+            winit::event::WindowEvent::ThemeChanged(theme) => {
+                let scheme = match theme {
+                    winit::window::Theme::Light => ColorScheme::Light,
+                    winit::window::Theme::Dark => ColorScheme::Dark,
+                };
+                vec![WindowEvent::ThemeChanged(scheme)]
+            }
+            winit::event::WindowEvent::HoveredFile(path) => vec![WindowEvent::FileHovered(path)],
+            winit::event::WindowEvent::HoveredFileCancelled => {
+                vec![WindowEvent::FileHoverCancelled]
+            }
+            winit::event::WindowEvent::DroppedFile(path) => vec![WindowEvent::FileDropped(path)],
+            _ => vec![],
+        }
+    }
+}
+
+impl WindowInterface for WinitWindow {
+    /// Runs the window's own event loop until the window is closed. A window
+    /// opened by an [`Application`](super::Application) is only made visible.
+    fn show(mut self) {
+        let Some(event_loop) = self._winit_eventloop.take() else {
+            return self._winit.set_visible(true);
+        };
+        let mut renderer = match pollster::block_on(WgpuRenderer::new(self._winit.clone())) {
+            Ok(renderer) => renderer,
+            Err(error) => return log::error!("{}", error),
+        };
+
+        let mut input = InputState::default();
+        run_event_loop(event_loop, move |event, _, control_flow| match event {
+            winit::event::Event::NewEvents(winit::event::StartCause::Init) => {
+                self.trigger(WindowEvent::WindowOpened);
+            }
+            winit::event::Event::RedrawRequested(_) => {
+                self.trigger(WindowEvent::RedrawRequested);
+                // Whatever was painted for this redraw is rendered right away.
+                self.surface.take_dirty();
+                renderer.clear(Theme::current().palette.background);
+                renderer.render(&self.surface.display_list())
+            }
+            winit::event::Event::MainEventsCleared if self.surface.take_dirty() => {
+                self._winit.request_redraw();
+            }
+            winit::event::Event::WindowEvent { event, .. } => {
+                for event in self.translate(event, &mut renderer, &mut input) {
+                    self.trigger(event.clone());
+                    if let WindowEvent::CloseRequested(request) = event {
+                        if !request.is_cancelled() {
+                            self.trigger(WindowEvent::WindowClosed);
+                            control_flow.set_exit();
+                        }
                     }
-                    _ => (),
                 }
             }
             _ => (),
-        })
+        });
+    }
+    fn hide(&mut self) {
+        self._winit.set_visible(false);
+    }
+    fn set_title(&mut self, title: &str) {
+        self._winit.set_title(title);
+    }
+    fn get_title(&self) -> String {
+        self._winit.title()
     }
-    fn hide(&self) {}
-    fn set_title(&self) {}
     fn set_size(&mut self, width: f32, height: f32) {
-        self.width = width;
-        self.height = height;
         self._winit
-            .set_inner_size(winit::dpi::LogicalSize::<f32>::new(width, height));
-    }
-    fn get_size(&self) {}
-    fn set_pos(&self) {}
-    fn get_pos(&self) {}
-    fn set_resizeable(&self) {}
-    fn is_resizeable(&self) {}
-    fn set_fullscreen(&self) {}
+            .set_inner_size(winit::dpi::PhysicalSize::<f32>::new(width, height));
+    }
+    fn get_size(&self) -> (f32, f32) {
+        let size = self._winit.inner_size();
+        (size.width as f32, size.height as f32)
+    }
+    fn set_pos(&mut self, x: f32, y: f32) {
+        self._winit
+            .set_outer_position(winit::dpi::PhysicalPosition::<f32>::new(x, y));
+    }
+    /// `(0, 0)` on platforms that do not report window positions.
+    fn get_pos(&self) -> (f32, f32) {
+        self._winit.outer_position().map_or((0.0, 0.0), |position| {
+            (position.x as f32, position.y as f32)
+        })
+    }
+    fn set_resizeable(&mut self, resizeable: bool) {
+        self._winit.set_resizable(resizeable);
+    }
+    fn is_resizeable(&self) -> bool {
+        self._winit.is_resizable()
+    }
+    fn set_fullscreen(&mut self, fullscreen: bool) {
+        self._winit
+            .set_fullscreen(fullscreen.then_some(winit::window::Fullscreen::Borderless(None)));
+    }
+    fn is_fullscreen(&self) -> bool {
+        self._winit.fullscreen().is_some()
+    }
+    fn set_min_size(&mut self, size: Option<(f32, f32)>) {
+        self._winit.set_min_inner_size(
+            size.map(|(width, height)| winit::dpi::PhysicalSize::<f32>::new(width, height)),
+        );
+    }
+    fn set_max_size(&mut self, size: Option<(f32, f32)>) {
+        self._winit.set_max_inner_size(
+            size.map(|(width, height)| winit::dpi::PhysicalSize::<f32>::new(width, height)),
+        );
+    }
+    fn set_decorations(&mut self, decorations: bool) {
+        self._winit.set_decorations(decorations);
+    }
+    fn has_decorations(&self) -> bool {
+        self._winit.is_decorated()
+    }
     fn handle_events<'a, F>(&self, fun: F)
     where
         F: Fn(WindowEvent) + 'a + 'static,
     {
-        self.events.lock().unwrap().subscribe(fun);
+        self.events.borrow().subscribe(fun).detach();
     }
-    fn close(&self) {
-        self.events
-            .lock()
-            .unwrap()
-            .trigger(WindowEvent::WindowClosed);
+    fn close(&mut self) {
+        self.events.borrow().trigger(WindowEvent::WindowClosed);
+    }
+    fn surface(&self) -> Surface {
+        self.surface.clone()
+    }
+    fn screen(&self) -> Viewport {
+        let size = self
+            ._winit
+            .current_monitor()
+            .map(|monitor| monitor.size())
+            .unwrap_or_default();
+        Viewport {
+            width: size.width as f32,
+            height: size.height as f32,
+            scale_factor: self._winit.scale_factor() as f32,
+        }
+    }
+}
+/// Pixels scrolled per line by wheels that report whole lines.
+const LINE_SCROLL: f32 = 40.0;
+
+fn pointer_button(button: winit::event::MouseButton) -> PointerButton {
+    match button {
+        winit::event::MouseButton::Left => PointerButton::Primary,
+        winit::event::MouseButton::Right => PointerButton::Secondary,
+        winit::event::MouseButton::Middle => PointerButton::Middle,
+        winit::event::MouseButton::Other(button) => PointerButton::Other(button),
     }
 }
+
+fn key(key: winit::event::VirtualKeyCode) -> Key {
+    use winit::event::VirtualKeyCode as Code;
+    match key {
+        Code::Key1 | Code::Numpad1 => Key::Character('1'),
+        Code::Key2 | Code::Numpad2 => Key::Character('2'),
+        Code::Key3 | Code::Numpad3 => Key::Character('3'),
+        Code::Key4 | Code::Numpad4 => Key::Character('4'),
+        Code::Key5 | Code::Numpad5 => Key::Character('5'),
+        Code::Key6 | Code::Numpad6 => Key::Character('6'),
+        Code::Key7 | Code::Numpad7 => Key::Character('7'),
+        Code::Key8 | Code::Numpad8 => Key::Character('8'),
+        Code::Key9 | Code::Numpad9 => Key::Character('9'),
+        Code::Key0 | Code::Numpad0 => Key::Character('0'),
+        Code::A => Key::Character('a'),
+        Code::B => Key::Character('b'),
+        Code::C => Key::Character('c'),
+        Code::D => Key::Character('d'),
+        Code::E => Key::Character('e'),
+        Code::F => Key::Character('f'),
+        Code::G => Key::Character('g'),
+        Code::H => Key::Character('h'),
+        Code::I => Key::Character('i'),
+        Code::J => Key::Character('j'),
+        Code::K => Key::Character('k'),
+        Code::L => Key::Character('l'),
+        Code::M => Key::Character('m'),
+        Code::N => Key::Character('n'),
+        Code::O => Key::Character('o'),
+        Code::P => Key::Character('p'),
+        Code::Q => Key::Character('q'),
+        Code::R => Key::Character('r'),
+        Code::S => Key::Character('s'),
+        Code::T => Key::Character('t'),
+        Code::U => Key::Character('u'),
+        Code::V => Key::Character('v'),
+        Code::W => Key::Character('w'),
+        Code::X => Key::Character('x'),
+        Code::Y => Key::Character('y'),
+        Code::Z => Key::Character('z'),
+        Code::Minus | Code::NumpadSubtract => Key::Character('-'),
+        Code::Equals | Code::NumpadEquals => Key::Character('='),
+        Code::Plus | Code::NumpadAdd => Key::Character('+'),
+        Code::Asterisk | Code::NumpadMultiply => Key::Character('*'),
+        Code::Slash | Code::NumpadDivide => Key::Character('/'),
+        Code::Period | Code::NumpadDecimal => Key::Character('.'),
+        Code::Comma | Code::NumpadComma => Key::Character(','),
+        Code::Semicolon => Key::Character(';'),
+        Code::Apostrophe => Key::Character('\''),
+        Code::Grave => Key::Character('`'),
+        Code::Backslash => Key::Character('\\'),
+        Code::LBracket => Key::Character('['),
+        Code::RBracket => Key::Character(']'),
+        Code::Return | Code::NumpadEnter => Key::Enter,
+        Code::Tab => Key::Tab,
+        Code::Space => Key::Space,
+        Code::Back => Key::Backspace,
+        Code::Delete => Key::Delete,
+        Code::Escape => Key::Escape,
+        Code::Insert => Key::Insert,
+        Code::Left => Key::ArrowLeft,
+        Code::Right => Key::ArrowRight,
+        Code::Up => Key::ArrowUp,
+        Code::Down => Key::ArrowDown,
+        Code::Home => Key::Home,
+        Code::End => Key::End,
+        Code::PageUp => Key::PageUp,
+        Code::PageDown => Key::PageDown,
+        Code::F1 => Key::F(1),
+        Code::F2 => Key::F(2),
+        Code::F3 => Key::F(3),
+        Code::F4 => Key::F(4),
+        Code::F5 => Key::F(5),
+        Code::F6 => Key::F(6),
+        Code::F7 => Key::F(7),
+        Code::F8 => Key::F(8),
+        Code::F9 => Key::F(9),
+        Code::F10 => Key::F(10),
+        Code::F11 => Key::F(11),
+        Code::F12 => Key::F(12),
+        Code::LShift | Code::RShift => Key::Shift,
+        Code::LControl | Code::RControl => Key::Control,
+        Code::LAlt | Code::RAlt => Key::Alt,
+        Code::LWin | Code::RWin => Key::Logo,
+        _ => Key::Unidentified,
+    }
+}
+
 pub struct Window {}
 
 impl Window {
-    pub fn start(title: &str) -> impl WindowInterface {
-        #[cfg(test)]
-        let _winit_eventloop = event_loop::EventLoop::from(
-            event_loop::EventLoopBuilder::new()
-                .with_any_thread(true)
-                .build(),
-        );
-        #[cfg(not(test))]
-        let _winit_eventloop = event_loop::EventLoop::new();
+    /// Creates a native window with its own event loop, run by
+    /// [`show`](WindowInterface::show).
+    pub fn start(title: &str) -> crate::Result<impl WindowInterface> {
+        let _winit_eventloop = new_event_loop();
 
         let _winit = winit::window::WindowBuilder::new()
             .with_title(title)
-            .build(&_winit_eventloop)
-            .expect("Window could not be created");
-        let window = WinitWindow {
-            width: 0.,
-            height: 0.,
-            posx: 0.,
-            posy: 0.,
-            resizeable: true,
-            fullscreen: false,
-            events: Arc::new(Mutex::new(Event::new())),
-            _winit,
-            _winit_eventloop,
-        };
-        return window;
+            .build(&_winit_eventloop)?;
+        Ok(WinitWindow {
+            _winit_eventloop: Some(_winit_eventloop),
+            ..WinitWindow::new(_winit)
+        })
+    }
+
+    /// Creates a window without a native backend, see [`HeadlessWindow`].
+    pub fn headless(title: &str) -> HeadlessWindow {
+        HeadlessWindow::new(title)
     }
 }
+
+/// Creates the winit event loop used by every platform window.
+///
+/// Test builds run on the test harness threads rather than the main thread, so
+/// they opt into the platform's `with_any_thread` extension where one exists.
+pub(crate) fn new_event_loop<T: 'static>() -> event_loop::EventLoop<T> {
+    let mut builder = event_loop::EventLoopBuilder::with_user_event();
+    #[cfg(test)]
+    allow_any_thread(&mut builder);
+    builder.build()
+}
+
+/// Runs `event_loop` until `handler` asks it to exit, and returns the exit
+/// code. The last event `handler` sees is `LoopDestroyed`.
+#[cfg(not(any(target_arch = "wasm32", target_os = "ios")))]
+pub(crate) fn run_event_loop<T: 'static>(
+    mut event_loop: event_loop::EventLoop<T>,
+    handler: impl FnMut(
+            winit::event::Event<'_, T>,
+            &event_loop::EventLoopWindowTarget<T>,
+            &mut event_loop::ControlFlow,
+        ) + 'static,
+) -> i32 {
+    use winit::platform::run_return::EventLoopExtRunReturn;
+    event_loop.run_return(handler)
+}
+
+// The web and iOS never give control back once their event loop runs.
+#[cfg(any(target_arch = "wasm32", target_os = "ios"))]
+pub(crate) fn run_event_loop<T: 'static>(
+    event_loop: event_loop::EventLoop<T>,
+    handler: impl FnMut(
+            winit::event::Event<'_, T>,
+            &event_loop::EventLoopWindowTarget<T>,
+            &mut event_loop::ControlFlow,
+        ) + 'static,
+) -> i32 {
+    event_loop.run(handler)
+}
+
+#[cfg(all(test, target_os = "windows"))]
+fn allow_any_thread<T: 'static>(builder: &mut event_loop::EventLoopBuilder<T>) {
+    use winit::platform::windows::EventLoopBuilderExtWindows;
+    builder.with_any_thread(true);
+}
+
+#[cfg(all(
+    test,
+    any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd",
+    )
+))]
+fn allow_any_thread<T: 'static>(builder: &mut event_loop::EventLoopBuilder<T>) {
+    // winit prefers wayland whenever a compositor is advertised, so follow the
+    // same choice when picking the extension trait.
+    if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        use winit::platform::wayland::EventLoopBuilderExtWayland;
+        builder.with_any_thread(true);
+    } else {
+        use winit::platform::x11::EventLoopBuilderExtX11;
+        builder.with_any_thread(true);
+    }
+}
+
+// macOS, iOS and the web only ever drive the event loop from the main thread.
+#[cfg(all(
+    test,
+    not(any(
+        target_os = "windows",
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd",
+    ))
+))]
+fn allow_any_thread<T: 'static>(_builder: &mut event_loop::EventLoopBuilder<T>) {}
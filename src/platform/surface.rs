@@ -0,0 +1,41 @@
+use std::{
+    cell::{Ref, RefCell},
+    rc::Rc,
+};
+
+use crate::renderer::DisplayList;
+
+/// What a window shows. Clones share the same display list, so that event
+/// handlers can repaint a window they do not own.
+#[derive(Debug, Clone, Default)]
+pub struct Surface {
+    inner: Rc<RefCell<SurfaceState>>,
+}
+
+#[derive(Debug, Default)]
+struct SurfaceState {
+    list: DisplayList,
+    dirty: bool,
+}
+
+impl Surface {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replaces what the window shows, it is redrawn on the next frame.
+    pub fn draw(&self, list: DisplayList) {
+        let mut state = self.inner.borrow_mut();
+        state.list = list;
+        state.dirty = true;
+    }
+
+    pub fn display_list(&self) -> Ref<'_, DisplayList> {
+        Ref::map(self.inner.borrow(), |state| &state.list)
+    }
+
+    /// Whether the display list changed since the last call.
+    pub fn take_dirty(&self) -> bool {
+        std::mem::take(&mut self.inner.borrow_mut().dirty)
+    }
+}
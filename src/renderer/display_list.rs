@@ -0,0 +1,227 @@
+use std::sync::Arc;
+
+use super::Image;
+use crate::text::{Font, TextStyle};
+use crate::utils::{Color, Rect};
+
+/// A 2D transform limited to scaling followed by a translation, which keeps
+/// rectangles axis aligned for every backend.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform {
+    pub scale_x: f32,
+    pub scale_y: f32,
+    pub translate_x: f32,
+    pub translate_y: f32,
+}
+
+impl Transform {
+    pub const IDENTITY: Transform = Transform {
+        scale_x: 1.0,
+        scale_y: 1.0,
+        translate_x: 0.0,
+        translate_y: 0.0,
+    };
+
+    pub fn translate(x: f32, y: f32) -> Self {
+        Self {
+            translate_x: x,
+            translate_y: y,
+            ..Self::IDENTITY
+        }
+    }
+
+    pub fn scale(x: f32, y: f32) -> Self {
+        Self {
+            scale_x: x,
+            scale_y: y,
+            ..Self::IDENTITY
+        }
+    }
+
+    /// Applies `self` after `inner`.
+    pub fn then(&self, inner: &Transform) -> Transform {
+        Transform {
+            scale_x: self.scale_x * inner.scale_x,
+            scale_y: self.scale_y * inner.scale_y,
+            translate_x: self.scale_x * inner.translate_x + self.translate_x,
+            translate_y: self.scale_y * inner.translate_y + self.translate_y,
+        }
+    }
+
+    pub fn apply(&self, rect: &Rect) -> Rect {
+        Rect::new(
+            rect.x * self.scale_x + self.translate_x,
+            rect.y * self.scale_y + self.translate_y,
+            rect.width * self.scale_x,
+            rect.height * self.scale_y,
+        )
+    }
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+/// A single line of text positioned by the top-left corner of its line box.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GlyphRun {
+    pub text: String,
+    pub x: f32,
+    pub y: f32,
+    /// Size of the font in pixels.
+    pub size: f32,
+    pub color: Color,
+    /// The font to draw with, the built-in bitmap font if unset.
+    pub font: Option<Font>,
+}
+
+impl GlyphRun {
+    pub fn style(&self) -> TextStyle {
+        TextStyle {
+            font: self.font.clone(),
+            size: self.size,
+        }
+    }
+}
+
+/// A backend-agnostic drawing command.
+///
+/// The `Push*` commands apply until their matching `Pop*`, and may nest.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Primitive {
+    Rect {
+        rect: Rect,
+        color: Color,
+    },
+    RoundedRect {
+        rect: Rect,
+        radius: f32,
+        color: Color,
+    },
+    /// A stroke of `width` pixels along the inside of `rect`.
+    Border {
+        rect: Rect,
+        width: f32,
+        radius: f32,
+        color: Color,
+    },
+    Glyphs(GlyphRun),
+    /// The image stretched over `rect`.
+    Image {
+        image: Arc<Image>,
+        rect: Rect,
+    },
+    PushClip(Rect),
+    PopClip,
+    PushTransform(Transform),
+    PopTransform,
+    /// Draws everything up to `PopLayer` on its own surface, then composites
+    /// it with the given opacity.
+    PushLayer {
+        opacity: f32,
+    },
+    PopLayer,
+}
+
+/// The retained list of primitives produced by painting a widget tree.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DisplayList {
+    primitives: Vec<Primitive>,
+}
+
+impl DisplayList {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn primitives(&self) -> &[Primitive] {
+        &self.primitives
+    }
+
+    pub fn len(&self) -> usize {
+        self.primitives.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.primitives.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.primitives.clear();
+    }
+
+    pub fn push(&mut self, primitive: Primitive) {
+        self.primitives.push(primitive);
+    }
+
+    pub fn rect(&mut self, rect: Rect, color: Color) {
+        self.push(Primitive::Rect { rect, color });
+    }
+
+    pub fn rounded_rect(&mut self, rect: Rect, radius: f32, color: Color) {
+        self.push(Primitive::RoundedRect {
+            rect,
+            radius,
+            color,
+        });
+    }
+
+    pub fn border(&mut self, rect: Rect, width: f32, radius: f32, color: Color) {
+        self.push(Primitive::Border {
+            rect,
+            width,
+            radius,
+            color,
+        });
+    }
+
+    pub fn text(&mut self, text: &str, x: f32, y: f32, size: f32, color: Color) {
+        self.push(Primitive::Glyphs(GlyphRun {
+            text: text.to_string(),
+            x,
+            y,
+            size,
+            color,
+            font: None,
+        }));
+    }
+
+    /// Draws one line of `text` in the font and size of `style`.
+    pub fn styled_text(&mut self, text: &str, x: f32, y: f32, style: &TextStyle, color: Color) {
+        self.push(Primitive::Glyphs(GlyphRun {
+            text: text.to_string(),
+            x,
+            y,
+            size: style.size,
+            color,
+            font: style.font.clone(),
+        }));
+    }
+
+    pub fn image(&mut self, image: Arc<Image>, rect: Rect) {
+        self.push(Primitive::Image { image, rect });
+    }
+
+    /// Restricts the primitives pushed by `paint` to `rect`.
+    pub fn with_clip(&mut self, rect: Rect, paint: impl FnOnce(&mut DisplayList)) {
+        self.push(Primitive::PushClip(rect));
+        paint(self);
+        self.push(Primitive::PopClip);
+    }
+
+    /// Transforms the primitives pushed by `paint`.
+    pub fn with_transform(&mut self, transform: Transform, paint: impl FnOnce(&mut DisplayList)) {
+        self.push(Primitive::PushTransform(transform));
+        paint(self);
+        self.push(Primitive::PopTransform);
+    }
+
+    /// Composites the primitives pushed by `paint` as one layer.
+    pub fn with_layer(&mut self, opacity: f32, paint: impl FnOnce(&mut DisplayList)) {
+        self.push(Primitive::PushLayer { opacity });
+        paint(self);
+        self.push(Primitive::PopLayer);
+    }
+}
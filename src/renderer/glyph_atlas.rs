@@ -0,0 +1,215 @@
+use std::collections::HashMap;
+
+use ab_glyph::{Font as _, GlyphId};
+
+use crate::text::Font;
+
+/// Empty pixels kept around every glyph so that filtering never bleeds.
+const PADDING: u32 = 1;
+
+/// A glyph rasterized into a [`GlyphAtlas`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AtlasEntry {
+    /// The glyph's pixels in the atlas.
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+    /// Offset of the top-left pixel from the glyph origin on the baseline.
+    pub left: f32,
+    pub top: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct GlyphKey {
+    font: u64,
+    glyph: u16,
+    /// The size in quarter pixels, so that nearly equal sizes share a bitmap.
+    size: u32,
+}
+
+/// A single-channel texture caching rasterized glyphs, packed into shelves.
+///
+/// Both renderers draw text from an atlas: the software renderer reads the
+/// coverage directly and the wgpu renderer uploads it as a texture whenever
+/// [`version`](Self::version) changes. The top-left texel is always fully
+/// covered, for geometry that is not textured.
+///
+/// The atlas grows up to [`MAX_SIZE`](Self::MAX_SIZE) texels along each side.
+/// Once that is full it is cleared and starts over with the glyphs drawn from
+/// then on, see [`generation`](Self::generation).
+#[derive(Debug, Clone)]
+pub struct GlyphAtlas {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+    entries: HashMap<GlyphKey, Option<AtlasEntry>>,
+    shelf_x: u32,
+    shelf_y: u32,
+    shelf_height: u32,
+    version: u64,
+    generation: u64,
+}
+
+impl Default for GlyphAtlas {
+    fn default() -> Self {
+        Self::new(256, 256)
+    }
+}
+
+impl GlyphAtlas {
+    /// The largest width and height the atlas grows to, which every graphics
+    /// backend supports for textures.
+    pub const MAX_SIZE: u32 = 4096;
+
+    pub fn new(width: u32, height: u32) -> Self {
+        let (width, height) = (
+            width.clamp(2 + PADDING, Self::MAX_SIZE),
+            height.clamp(2 + PADDING, Self::MAX_SIZE),
+        );
+        let mut pixels = vec![0; (width * height) as usize];
+        pixels[0] = 255;
+        Self {
+            width,
+            height,
+            pixels,
+            entries: HashMap::new(),
+            shelf_x: 1 + PADDING,
+            shelf_y: 0,
+            shelf_height: 1,
+            version: 0,
+            generation: 0,
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// The coverage of every texel, row by row from the top-left corner.
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    pub fn coverage(&self, x: u32, y: u32) -> u8 {
+        self.pixels[(y * self.width + x) as usize]
+    }
+
+    /// Changes whenever texels are added or the atlas grows.
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// Changes whenever the atlas was full and cleared, which invalidates the
+    /// entries handed out before.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Drops every glyph, keeping the size the atlas grew to.
+    pub fn clear(&mut self) {
+        self.pixels.fill(0);
+        self.pixels[0] = 255;
+        self.entries.clear();
+        self.shelf_x = 1 + PADDING;
+        self.shelf_y = 0;
+        self.shelf_height = 1;
+        self.version += 1;
+        self.generation += 1;
+    }
+
+    /// The glyph rasterized at `size` pixels, adding it on first use. Glyphs
+    /// without an outline, such as spaces, and glyphs too large for the atlas
+    /// have no entry.
+    pub fn glyph(&mut self, font: &Font, glyph: u16, size: f32) -> Option<AtlasEntry> {
+        let key = GlyphKey {
+            font: font.id(),
+            glyph,
+            size: (size * 4.0).round().max(0.0) as u32,
+        };
+        if let Some(entry) = self.entries.get(&key) {
+            return *entry;
+        }
+        let entry = self.rasterize(font, glyph, key.size as f32 / 4.0);
+        self.entries.insert(key, entry);
+        entry
+    }
+
+    fn rasterize(&mut self, font: &Font, glyph: u16, size: f32) -> Option<AtlasEntry> {
+        let glyph = GlyphId(glyph).with_scale(size);
+        let outline = font.scaled(size).font.outline_glyph(glyph)?;
+        let bounds = outline.px_bounds();
+        let (width, height) = (bounds.width() as u32, bounds.height() as u32);
+        if width == 0 || height == 0 {
+            return None;
+        }
+        let (x, y) = self.allocate(width, height)?;
+        outline.draw(|column, row, coverage| {
+            if column < width && row < height {
+                let i = ((y + row) * self.width + x + column) as usize;
+                self.pixels[i] = (coverage.clamp(0.0, 1.0) * 255.0).round() as u8;
+            }
+        });
+        self.version += 1;
+        Some(AtlasEntry {
+            x,
+            y,
+            width,
+            height,
+            left: bounds.min.x,
+            top: bounds.min.y,
+        })
+    }
+
+    /// Finds room for a `width` by `height` bitmap, growing the atlas when
+    /// it is full and clearing it once it cannot grow any further.
+    fn allocate(&mut self, width: u32, height: u32) -> Option<(u32, u32)> {
+        // Room for the bitmap below the first shelf of a cleared atlas.
+        if width.max(height) + 2 + 2 * PADDING > Self::MAX_SIZE {
+            return None;
+        }
+        while width + PADDING > self.width {
+            self.grow((self.width * 2).min(Self::MAX_SIZE), self.height);
+        }
+        if self.shelf_x + width + PADDING > self.width {
+            self.shelf_y += self.shelf_height + PADDING;
+            self.shelf_x = 0;
+            self.shelf_height = 0;
+        }
+        while self.shelf_y + height + PADDING > self.height {
+            // Grow the shorter side, so that later shelves get longer too.
+            if self.height < Self::MAX_SIZE
+                && (self.height <= self.width || self.width == Self::MAX_SIZE)
+            {
+                self.grow(self.width, (self.height * 2).min(Self::MAX_SIZE));
+            } else if self.width < Self::MAX_SIZE {
+                self.grow((self.width * 2).min(Self::MAX_SIZE), self.height);
+            } else {
+                self.clear();
+                return self.allocate(width, height);
+            }
+        }
+        let position = (self.shelf_x, self.shelf_y);
+        self.shelf_x += width + PADDING;
+        self.shelf_height = self.shelf_height.max(height);
+        Some(position)
+    }
+
+    fn grow(&mut self, width: u32, height: u32) {
+        let mut pixels = vec![0; (width * height) as usize];
+        for row in 0..self.height {
+            let from = (row * self.width) as usize;
+            let to = (row * width) as usize;
+            pixels[to..to + self.width as usize]
+                .copy_from_slice(&self.pixels[from..from + self.width as usize]);
+        }
+        self.width = width;
+        self.height = height;
+        self.pixels = pixels;
+        self.version += 1;
+    }
+}
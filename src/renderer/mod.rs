@@ -1,3 +1,13 @@
+pub mod bitmap_font;
+pub mod display_list;
+pub mod glyph_atlas;
+pub mod image;
 pub mod renderer;
+pub mod software;
 pub mod wgpu;
+pub use self::wgpu::*;
+pub use display_list::*;
+pub use glyph_atlas::*;
+pub use image::*;
 pub use renderer::*;
+pub use software::*;
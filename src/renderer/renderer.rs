@@ -1,6 +1,17 @@
-// Simple placeholder
+use super::DisplayList;
+use crate::utils::Color;
+use crate::Result;
+
+/// A drawing backend consuming the [`DisplayList`] painted by a widget tree.
 pub trait Renderer {
-    fn render_text(&self, text: &str);
-    fn render_image(&self, path: &str);
-    fn render_button(&self, label: &str);
+    /// Fills the whole target with `color`.
+    fn clear(&mut self, color: Color);
+    /// Draws every primitive of `list` in order, over the current contents.
+    fn try_render(&mut self, list: &DisplayList) -> Result<()>;
+    /// Like [`try_render`](Self::try_render), logging what went wrong.
+    fn render(&mut self, list: &DisplayList) {
+        if let Err(error) = self.try_render(list) {
+            log::error!("{}", error);
+        }
+    }
 }
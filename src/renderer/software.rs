@@ -0,0 +1,367 @@
+use super::{
+    bitmap_font, DisplayList, GlyphAtlas, GlyphRun, Image, Primitive, Renderer, Transform,
+};
+use crate::text::{shape, TextStyle};
+use crate::utils::{Color, Rect};
+use crate::Result;
+
+/// An RGBA8 pixel buffer with straight alpha, the target of [`SoftwareRenderer`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Framebuffer {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+}
+
+impl Framebuffer {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![0; width as usize * height as usize * 4],
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    pub fn pixel(&self, x: u32, y: u32) -> [u8; 4] {
+        let i = self.index(x, y);
+        [
+            self.pixels[i],
+            self.pixels[i + 1],
+            self.pixels[i + 2],
+            self.pixels[i + 3],
+        ]
+    }
+
+    pub fn fill(&mut self, color: Color) {
+        let rgba = color.to_rgba8();
+        for pixel in self.pixels.chunks_exact_mut(4) {
+            pixel.copy_from_slice(&rgba);
+        }
+    }
+
+    /// Composites `color` over the pixel, scaled by `coverage` in `0.0..=1.0`.
+    pub fn blend(&mut self, x: u32, y: u32, color: Color, coverage: f32) {
+        let alpha = color.a * coverage.clamp(0.0, 1.0);
+        if alpha <= 0.0 || x >= self.width || y >= self.height {
+            return;
+        }
+        let i = self.index(x, y);
+        let dst = &mut self.pixels[i..i + 4];
+        let dst_alpha = dst[3] as f32 / 255.0;
+        let out_alpha = alpha + dst_alpha * (1.0 - alpha);
+        let mix = |src: f32, dst: u8| {
+            let dst = dst as f32 / 255.0;
+            let value = (src * alpha + dst * dst_alpha * (1.0 - alpha)) / out_alpha;
+            (value.clamp(0.0, 1.0) * 255.0).round() as u8
+        };
+        dst[0] = mix(color.r, dst[0]);
+        dst[1] = mix(color.g, dst[1]);
+        dst[2] = mix(color.b, dst[2]);
+        dst[3] = (out_alpha * 255.0).round() as u8;
+    }
+
+    /// Converts the framebuffer into an [`Image`], e.g. to draw or compare it.
+    pub fn to_image(&self) -> Image {
+        Image::from_rgba(self.width, self.height, self.pixels.clone())
+            .expect("framebuffer size matches its pixels")
+    }
+
+    /// Composites another framebuffer of the same size over this one.
+    pub fn composite(&mut self, layer: &Framebuffer, opacity: f32) {
+        for y in 0..self.height.min(layer.height) {
+            for x in 0..self.width.min(layer.width) {
+                let [r, g, b, a] = layer.pixel(x, y);
+                self.blend(x, y, Color::from_rgba8(r, g, b, a), opacity);
+            }
+        }
+    }
+
+    fn index(&self, x: u32, y: u32) -> usize {
+        (y as usize * self.width as usize + x as usize) * 4
+    }
+
+    /// The pixel range touched by `rect`, clamped to the framebuffer.
+    fn span(&self, rect: &Rect) -> Option<(u32, u32, u32, u32)> {
+        let bounds = Rect::new(0.0, 0.0, self.width as f32, self.height as f32);
+        let visible = rect.intersect(&bounds)?;
+        Some((
+            visible.x.floor() as u32,
+            visible.y.floor() as u32,
+            (visible.right().ceil() as u32).min(self.width),
+            (visible.bottom().ceil() as u32).min(self.height),
+        ))
+    }
+}
+
+/// A renderer drawing into a [`Framebuffer`] on the CPU, for machines without
+/// a GPU and for comparing rendered output in tests.
+#[derive(Debug, Clone)]
+pub struct SoftwareRenderer {
+    framebuffer: Framebuffer,
+    atlas: GlyphAtlas,
+}
+
+/// The clip and transform in effect for a primitive.
+#[derive(Clone, Copy)]
+struct State {
+    clip: Rect,
+    transform: Transform,
+}
+
+impl SoftwareRenderer {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            framebuffer: Framebuffer::new(width, height),
+            atlas: GlyphAtlas::default(),
+        }
+    }
+
+    pub fn framebuffer(&self) -> &Framebuffer {
+        &self.framebuffer
+    }
+
+    pub fn into_framebuffer(self) -> Framebuffer {
+        self.framebuffer
+    }
+
+    /// Starts over with an empty framebuffer of the given size.
+    pub fn resize(&mut self, width: u32, height: u32) {
+        self.framebuffer = Framebuffer::new(width, height);
+    }
+
+    /// Blends `color` over every pixel of `bounds` inside the clip, weighted
+    /// by the coverage `shape` computes for the pixel center.
+    fn fill(&mut self, bounds: Rect, state: &State, color: Color, shape: impl Fn(f32, f32) -> f32) {
+        let Some(visible) = bounds.intersect(&state.clip) else {
+            return;
+        };
+        let Some((x0, y0, x1, y1)) = self.framebuffer.span(&visible) else {
+            return;
+        };
+        for y in y0..y1 {
+            for x in x0..x1 {
+                let (center_x, center_y) = (x as f32 + 0.5, y as f32 + 0.5);
+                if state.clip.contains(center_x, center_y) {
+                    self.framebuffer
+                        .blend(x, y, color, shape(center_x, center_y));
+                }
+            }
+        }
+    }
+
+    fn pop_layer(&mut self, mut below: Framebuffer, opacity: f32) {
+        below.composite(&self.framebuffer, opacity);
+        self.framebuffer = below;
+    }
+
+    fn draw_glyphs(&mut self, run: &GlyphRun, state: &State) {
+        let Some(font) = &run.font else {
+            return self.draw_bitmap_glyphs(run, state);
+        };
+        // Glyphs are rasterized at their final size rather than scaled.
+        let style = TextStyle {
+            size: run.size * state.transform.scale_y,
+            ..run.style()
+        };
+        let ascent = style.metrics().ascent;
+        let origin = state.transform.apply(&Rect::new(run.x, run.y, 0.0, 0.0));
+        for glyph in shape(&run.text, &style) {
+            let Some(entry) = self.atlas.glyph(font, glyph.id, style.size) else {
+                continue;
+            };
+            let left = (origin.x + glyph.x + entry.left).round() as i64;
+            let top = (origin.y + ascent + entry.top).round() as i64;
+            for row in 0..entry.height {
+                for column in 0..entry.width {
+                    let (x, y) = (left + column as i64, top + row as i64);
+                    if x < 0 || y < 0 || !state.clip.contains(x as f32 + 0.5, y as f32 + 0.5) {
+                        continue;
+                    }
+                    let coverage = self.atlas.coverage(entry.x + column, entry.y + row);
+                    self.framebuffer
+                        .blend(x as u32, y as u32, run.color, coverage as f32 / 255.0);
+                }
+            }
+        }
+    }
+
+    fn draw_bitmap_glyphs(&mut self, run: &GlyphRun, state: &State) {
+        let scale = bitmap_font::scale(run.size);
+        let mut pen_x = run.x;
+        for c in run.text.chars() {
+            let glyph = bitmap_font::glyph(c);
+            for column in 0..bitmap_font::GLYPH_WIDTH {
+                for row in 0..bitmap_font::GLYPH_HEIGHT {
+                    if bitmap_font::is_set(&glyph, column, row) {
+                        let cell = Rect::new(
+                            pen_x + column as f32 * scale,
+                            run.y + row as f32 * scale,
+                            scale,
+                            scale,
+                        );
+                        let cell = state.transform.apply(&cell);
+                        self.fill(cell, state, run.color, |x, y| rect_coverage(&cell, x, y));
+                    }
+                }
+            }
+            pen_x += bitmap_font::ADVANCE as f32 * scale;
+        }
+    }
+
+    fn draw_image(&mut self, image: &Image, rect: Rect, state: &State) {
+        if image.width() == 0 || image.height() == 0 {
+            return;
+        }
+        let Some(visible) = rect.intersect(&state.clip) else {
+            return;
+        };
+        let Some((x0, y0, x1, y1)) = self.framebuffer.span(&visible) else {
+            return;
+        };
+        for y in y0..y1 {
+            let v = (y as f32 + 0.5 - rect.y) / rect.height;
+            let src_y = ((v * image.height() as f32) as u32).min(image.height() - 1);
+            for x in x0..x1 {
+                let u = (x as f32 + 0.5 - rect.x) / rect.width;
+                let src_x = ((u * image.width() as f32) as u32).min(image.width() - 1);
+                let [r, g, b, a] = image.pixel(src_x, src_y);
+                self.framebuffer
+                    .blend(x, y, Color::from_rgba8(r, g, b, a), 1.0);
+            }
+        }
+    }
+}
+
+impl Renderer for SoftwareRenderer {
+    fn clear(&mut self, color: Color) {
+        self.framebuffer.fill(color);
+    }
+
+    /// Never fails, drawing to memory.
+    fn try_render(&mut self, list: &DisplayList) -> Result<()> {
+        let (width, height) = (self.framebuffer.width(), self.framebuffer.height());
+        let mut states = vec![State {
+            clip: Rect::new(0.0, 0.0, width as f32, height as f32),
+            transform: Transform::IDENTITY,
+        }];
+        // Every clip and transform pushes a state, layers additionally park
+        // the surface below them until they are popped.
+        let mut layers: Vec<(Framebuffer, f32, usize)> = Vec::new();
+
+        for primitive in list.primitives() {
+            let state = *states.last().expect("the root state is never popped");
+            match primitive {
+                Primitive::Rect { rect, color } => {
+                    let rect = state.transform.apply(rect);
+                    self.fill(rect, &state, *color, |x, y| rect_coverage(&rect, x, y));
+                }
+                Primitive::RoundedRect {
+                    rect,
+                    radius,
+                    color,
+                } => {
+                    let rect = state.transform.apply(rect);
+                    let radius = radius * state.transform.scale_x;
+                    self.fill(rect, &state, *color, |x, y| {
+                        rounded_coverage(&rect, radius, x, y)
+                    });
+                }
+                Primitive::Border {
+                    rect,
+                    width,
+                    radius,
+                    color,
+                } => {
+                    let outer = state.transform.apply(rect);
+                    let width = width * state.transform.scale_x;
+                    let radius = radius * state.transform.scale_x;
+                    let inner = Rect::new(
+                        outer.x + width,
+                        outer.y + width,
+                        (outer.width - 2.0 * width).max(0.0),
+                        (outer.height - 2.0 * width).max(0.0),
+                    );
+                    let inner_radius = (radius - width).max(0.0);
+                    self.fill(outer, &state, *color, |x, y| {
+                        rounded_coverage(&outer, radius, x, y)
+                            - rounded_coverage(&inner, inner_radius, x, y)
+                    });
+                }
+                Primitive::Glyphs(run) => self.draw_glyphs(run, &state),
+                Primitive::Image { image, rect } => {
+                    self.draw_image(image, state.transform.apply(rect), &state)
+                }
+                Primitive::PushClip(rect) => {
+                    let clip = state
+                        .transform
+                        .apply(rect)
+                        .intersect(&state.clip)
+                        .unwrap_or_default();
+                    states.push(State { clip, ..state });
+                }
+                Primitive::PushTransform(transform) => states.push(State {
+                    transform: state.transform.then(transform),
+                    ..state
+                }),
+                Primitive::PopClip | Primitive::PopTransform => {
+                    if states.len() > 1 {
+                        states.pop();
+                    }
+                }
+                Primitive::PushLayer { opacity } => {
+                    let below =
+                        std::mem::replace(&mut self.framebuffer, Framebuffer::new(width, height));
+                    layers.push((below, *opacity, states.len()));
+                }
+                Primitive::PopLayer => {
+                    if let Some((below, opacity, depth)) = layers.pop() {
+                        self.pop_layer(below, opacity);
+                        states.truncate(depth);
+                    }
+                }
+            }
+        }
+
+        // Unbalanced layers are still composited rather than dropped.
+        while let Some((below, opacity, _)) = layers.pop() {
+            self.pop_layer(below, opacity);
+        }
+        Ok(())
+    }
+}
+
+/// How much of the pixel centered on `x`, `y` lies inside `rect`.
+fn rect_coverage(rect: &Rect, x: f32, y: f32) -> f32 {
+    let cover_x = (rect.right().min(x + 0.5) - rect.x.max(x - 0.5)).clamp(0.0, 1.0);
+    let cover_y = (rect.bottom().min(y + 0.5) - rect.y.max(y - 0.5)).clamp(0.0, 1.0);
+    cover_x * cover_y
+}
+
+/// Approximate coverage of the pixel centered on `x`, `y` by a rounded
+/// rectangle, from the signed distance to its outline.
+fn rounded_coverage(rect: &Rect, radius: f32, x: f32, y: f32) -> f32 {
+    let radius = radius.min(rect.width / 2.0).min(rect.height / 2.0).max(0.0);
+    if radius == 0.0 {
+        return rect_coverage(rect, x, y);
+    }
+    let (half_w, half_h) = (rect.width / 2.0, rect.height / 2.0);
+    let offset_x = (x - (rect.x + half_w)).abs();
+    let offset_y = (y - (rect.y + half_h)).abs();
+    let dx = (offset_x - (half_w - radius)).max(0.0);
+    let dy = (offset_y - (half_h - radius)).max(0.0);
+    let distance = (dx * dx + dy * dy).sqrt() - radius;
+    let edge = (half_w - offset_x).min(half_h - offset_y);
+    (0.5 - distance).min(edge + 0.5).clamp(0.0, 1.0)
+}
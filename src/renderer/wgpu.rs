@@ -1 +1,866 @@
+use std::{ops::Range, sync::Arc};
 
+use wgpu::{
+    util::DeviceExt, Backends, BindGroupDescriptor, BindGroupLayoutDescriptor, Instance,
+    InstanceDescriptor, RequestAdapterOptions,
+};
+
+use super::{bitmap_font, DisplayList, GlyphAtlas, GlyphRun, Primitive, Renderer, Transform};
+use crate::error::{Error, Result};
+use crate::text::{shape, TextStyle};
+use crate::utils::{Color, Rect};
+
+const SHADER: &str = r#"
+
+struct VertexInput {
+    @location(0) position: vec2<f32>,
+    @location(1) color: vec4<f32>,
+    @location(2) uv: vec2<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+    @location(1) uv: vec2<f32>,
+};
+
+
+@group(0) @binding(0)
+var<uniform> window_size: vec2<f32>;
+
+@group(1) @binding(0)
+var atlas: texture_2d<f32>;
+@group(1) @binding(1)
+var atlas_sampler: sampler;
+
+@vertex
+fn vs_main(
+    model: VertexInput,
+) -> VertexOutput {
+
+    var out: VertexOutput;
+
+    var normalizationMatrix = mat2x2<f32>(
+        2.0 / window_size.x, 0.0,
+        0.0, -2.0 / window_size.y
+    );
+
+    var normalizedPosition = normalizationMatrix * model.position;
+    normalizedPosition.y += 1.0;
+    normalizedPosition.x -= 1.0;
+
+    out.clip_position = vec4<f32>(normalizedPosition, 0.0, 1.0);
+
+    out.color = model.color;
+    out.uv = model.uv;
+
+    return out;
+}
+
+
+// Fragment shader
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    // Texel coordinates stay valid when the atlas grows.
+    let coverage = textureSample(atlas, atlas_sampler, in.uv / vec2<f32>(textureDimensions(atlas))).r;
+    return vec4<f32>(in.color.rgb, in.color.a * coverage);
+}
+
+"#;
+
+/// Line segments used for each quarter circle of a rounded corner.
+const CORNER_SEGMENTS: usize = 6;
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+struct Vertex {
+    position: [f32; 2],
+    color: [f32; 4],
+    /// Atlas texel coordinates, the solid texel for untextured geometry.
+    uv: [f32; 2],
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+struct WindowSize {
+    size: [f32; 2],
+}
+
+unsafe impl bytemuck::Pod for Vertex {}
+unsafe impl bytemuck::Zeroable for Vertex {}
+
+unsafe impl bytemuck::Pod for WindowSize {}
+unsafe impl bytemuck::Zeroable for WindowSize {}
+
+impl Vertex {
+    const ATTRIBS: [wgpu::VertexAttribute; 3] =
+        wgpu::vertex_attr_array![0 => Float32x2, 1 => Float32x4, 2 => Float32x2];
+
+    /// The center of the atlas texel that is always fully covered.
+    const SOLID: [f32; 2] = [0.5, 0.5];
+
+    fn desc() -> wgpu::VertexBufferLayout<'static> {
+        use std::mem;
+
+        wgpu::VertexBufferLayout {
+            array_stride: mem::size_of::<Self>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &Self::ATTRIBS,
+        }
+    }
+}
+
+/// A run of indices drawn with the same scissor rectangle.
+struct Batch {
+    clip: Rect,
+    indices: Range<u32>,
+}
+
+/// The clip, transform and opacity in effect for a primitive.
+#[derive(Clone, Copy)]
+struct State {
+    clip: Rect,
+    transform: Transform,
+    opacity: f32,
+}
+
+/// Turns a display list into triangles, batched by clip rectangle.
+#[derive(Default)]
+struct Tessellator {
+    vertices: Vec<Vertex>,
+    indices: Vec<u32>,
+    batches: Vec<Batch>,
+    linear: bool,
+    atlas: GlyphAtlas,
+    /// The image primitives left out of the last display list.
+    skipped_images: usize,
+}
+
+impl Tessellator {
+    fn tessellate(&mut self, list: &DisplayList, width: f32, height: f32) {
+        self.vertices.clear();
+        self.indices.clear();
+        self.batches.clear();
+        self.skipped_images = 0;
+
+        let mut states = vec![State {
+            clip: Rect::new(0.0, 0.0, width, height),
+            transform: Transform::IDENTITY,
+            opacity: 1.0,
+        }];
+
+        for primitive in list.primitives() {
+            let state = *states.last().expect("the root state is never popped");
+            match primitive {
+                Primitive::Rect { rect, color } => {
+                    self.rounded_rect(&state, state.transform.apply(rect), 0.0, *color)
+                }
+                Primitive::RoundedRect {
+                    rect,
+                    radius,
+                    color,
+                } => self.rounded_rect(
+                    &state,
+                    state.transform.apply(rect),
+                    radius * state.transform.scale_x,
+                    *color,
+                ),
+                Primitive::Border {
+                    rect,
+                    width,
+                    radius,
+                    color,
+                } => self.border(
+                    &state,
+                    state.transform.apply(rect),
+                    width * state.transform.scale_x,
+                    radius * state.transform.scale_x,
+                    *color,
+                ),
+                Primitive::Glyphs(run) => self.glyphs(&state, run),
+                // Textures are not supported by the rectangle pipeline yet.
+                Primitive::Image { .. } => self.skipped_images += 1,
+                Primitive::PushClip(rect) => {
+                    let clip = state
+                        .transform
+                        .apply(rect)
+                        .intersect(&state.clip)
+                        .unwrap_or_default();
+                    states.push(State { clip, ..state });
+                }
+                Primitive::PushTransform(transform) => states.push(State {
+                    transform: state.transform.then(transform),
+                    ..state
+                }),
+                // Layers are not composited offscreen, their opacity is
+                // applied to every primitive instead.
+                Primitive::PushLayer { opacity } => states.push(State {
+                    opacity: state.opacity * opacity,
+                    ..state
+                }),
+                Primitive::PopClip | Primitive::PopTransform | Primitive::PopLayer => {
+                    if states.len() > 1 {
+                        states.pop();
+                    }
+                }
+            }
+        }
+    }
+
+    fn vertex(&self, state: &State, position: [f32; 2], color: Color) -> Vertex {
+        Vertex {
+            position,
+            color: self.encode(color, state.opacity),
+            uv: Vertex::SOLID,
+        }
+    }
+
+    /// The color as the surface expects it, with its alpha scaled by `opacity`.
+    fn encode(&self, color: Color, opacity: f32) -> [f32; 4] {
+        let channel = |value: f32| {
+            if self.linear {
+                // The surface expects linear values and encodes them to sRGB.
+                if value <= 0.04045 {
+                    value / 12.92
+                } else {
+                    ((value + 0.055) / 1.055).powf(2.4)
+                }
+            } else {
+                value
+            }
+        };
+        [
+            channel(color.r),
+            channel(color.g),
+            channel(color.b),
+            color.a * opacity,
+        ]
+    }
+
+    /// Appends triangles, opening a new batch when the clip changes.
+    fn push(&mut self, state: &State, vertices: Vec<Vertex>, indices: impl Iterator<Item = u32>) {
+        if state.clip.width <= 0.0 || state.clip.height <= 0.0 {
+            return;
+        }
+        let base = self.vertices.len() as u32;
+        self.vertices.extend(vertices);
+        self.indices.extend(indices.map(|index| base + index));
+        let end = self.indices.len() as u32;
+        match self.batches.last_mut() {
+            Some(batch) if batch.clip == state.clip => batch.indices.end = end,
+            last => {
+                let start = last.map_or(0, |batch| batch.indices.end);
+                self.batches.push(Batch {
+                    clip: state.clip,
+                    indices: start..end,
+                });
+            }
+        }
+    }
+
+    fn rounded_rect(&mut self, state: &State, rect: Rect, radius: f32, color: Color) {
+        if radius <= 0.0 {
+            let corners = [
+                [rect.x, rect.y],
+                [rect.right(), rect.y],
+                [rect.right(), rect.bottom()],
+                [rect.x, rect.bottom()],
+            ];
+            let vertices = corners
+                .iter()
+                .map(|p| self.vertex(state, *p, color))
+                .collect();
+            return self.push(state, vertices, [0, 2, 1, 0, 3, 2].into_iter());
+        }
+        let outline = outline(&rect, radius);
+        let center = [rect.x + rect.width / 2.0, rect.y + rect.height / 2.0];
+        let count = outline.len() as u32;
+        let mut vertices = vec![self.vertex(state, center, color)];
+        vertices.extend(outline.iter().map(|p| self.vertex(state, *p, color)));
+        let indices = (0..count).flat_map(|i| [0, 1 + (i + 1) % count, 1 + i]);
+        self.push(state, vertices, indices);
+    }
+
+    fn border(&mut self, state: &State, rect: Rect, width: f32, radius: f32, color: Color) {
+        let inner = Rect::new(
+            rect.x + width,
+            rect.y + width,
+            (rect.width - 2.0 * width).max(0.0),
+            (rect.height - 2.0 * width).max(0.0),
+        );
+        let outer = outline(&rect, radius);
+        let inner = outline(&inner, (radius - width).max(0.0));
+        let count = outer.len() as u32;
+        let vertices = outer
+            .iter()
+            .chain(inner.iter())
+            .map(|p| self.vertex(state, *p, color))
+            .collect();
+        let indices = (0..count).flat_map(move |i| {
+            let next = (i + 1) % count;
+            [i, count + next, next, i, count + i, count + next]
+        });
+        self.push(state, vertices, indices);
+    }
+
+    fn glyphs(&mut self, state: &State, run: &GlyphRun) {
+        let Some(font) = &run.font else {
+            return self.bitmap_glyphs(state, run);
+        };
+        // Glyphs are rasterized at their final size rather than scaled.
+        let style = TextStyle {
+            size: run.size * state.transform.scale_y,
+            ..run.style()
+        };
+        let ascent = style.metrics().ascent;
+        let origin = state.transform.apply(&Rect::new(run.x, run.y, 0.0, 0.0));
+        for glyph in shape(&run.text, &style) {
+            let Some(entry) = self.atlas.glyph(font, glyph.id, style.size) else {
+                continue;
+            };
+            let x = (origin.x + glyph.x + entry.left).round();
+            let y = (origin.y + ascent + entry.top).round();
+            let (width, height) = (entry.width as f32, entry.height as f32);
+            let (u, v) = (entry.x as f32, entry.y as f32);
+            let color = self.encode(run.color, state.opacity);
+            let vertices = [
+                ([x, y], [u, v]),
+                ([x + width, y], [u + width, v]),
+                ([x + width, y + height], [u + width, v + height]),
+                ([x, y + height], [u, v + height]),
+            ]
+            .into_iter()
+            .map(|(position, uv)| Vertex {
+                position,
+                color,
+                uv,
+            })
+            .collect();
+            self.push(state, vertices, [0, 2, 1, 0, 3, 2].into_iter());
+        }
+    }
+
+    fn bitmap_glyphs(&mut self, state: &State, run: &GlyphRun) {
+        let scale = bitmap_font::scale(run.size);
+        let mut pen_x = run.x;
+        for c in run.text.chars() {
+            let glyph = bitmap_font::glyph(c);
+            for column in 0..bitmap_font::GLYPH_WIDTH {
+                for row in 0..bitmap_font::GLYPH_HEIGHT {
+                    if bitmap_font::is_set(&glyph, column, row) {
+                        let cell = Rect::new(
+                            pen_x + column as f32 * scale,
+                            run.y + row as f32 * scale,
+                            scale,
+                            scale,
+                        );
+                        self.rounded_rect(state, state.transform.apply(&cell), 0.0, run.color);
+                    }
+                }
+            }
+            pen_x += bitmap_font::ADVANCE as f32 * scale;
+        }
+    }
+}
+
+/// Points around a rounded rectangle in clockwise screen order, always the
+/// same number of them so that outlines of different radii line up.
+fn outline(rect: &Rect, radius: f32) -> Vec<[f32; 2]> {
+    let radius = radius.min(rect.width / 2.0).min(rect.height / 2.0).max(0.0);
+    let corners = [
+        (rect.right() - radius, rect.y + radius, -90.0f32),
+        (rect.right() - radius, rect.bottom() - radius, 0.0),
+        (rect.x + radius, rect.bottom() - radius, 90.0),
+        (rect.x + radius, rect.y + radius, 180.0),
+    ];
+    let mut points = Vec::with_capacity(4 * (CORNER_SEGMENTS + 1));
+    for (x, y, start) in corners {
+        for step in 0..=CORNER_SEGMENTS {
+            let angle = (start + 90.0 * step as f32 / CORNER_SEGMENTS as f32).to_radians();
+            points.push([x + radius * angle.cos(), y + radius * angle.sin()]);
+        }
+    }
+    points
+}
+
+/// Draws display lists onto a native window with wgpu, batching every
+/// primitive into a single vertex and index buffer.
+///
+/// Unlike the [`SoftwareRenderer`](super::SoftwareRenderer), it does not
+/// draw images yet, and logs a warning for every frame that leaves some out.
+pub struct WgpuRenderer {
+    surface: wgpu::Surface,
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    config: wgpu::SurfaceConfiguration,
+    render_pipeline: wgpu::RenderPipeline,
+    vertex_buffer: wgpu::Buffer,
+    index_buffer: wgpu::Buffer,
+    window_size_buffer: wgpu::Buffer,
+    window_bind_group: wgpu::BindGroup,
+    atlas_bind_layout: wgpu::BindGroupLayout,
+    atlas_sampler: wgpu::Sampler,
+    atlas_texture: wgpu::Texture,
+    atlas_bind_group: wgpu::BindGroup,
+    /// The atlas version the texture holds.
+    atlas_version: Option<u64>,
+    tessellator: Tessellator,
+    clear_color: Color,
+    // Declared last so the surface is dropped before the window it draws to.
+    _window: Arc<winit::window::Window>,
+}
+
+impl WgpuRenderer {
+    /// Sets up drawing to `window`, failing if the platform has no graphics
+    /// adapter or device that can draw to it.
+    pub async fn new(window: Arc<winit::window::Window>) -> Result<Self> {
+        let size = window.inner_size();
+
+        let instance = Instance::new(InstanceDescriptor {
+            backends: Backends::PRIMARY,
+            ..InstanceDescriptor::default()
+        });
+
+        // The renderer keeps the window alive for as long as the surface.
+        let surface = unsafe { instance.create_surface(window.as_ref()) }?;
+
+        let options = RequestAdapterOptions {
+            power_preference: wgpu::PowerPreference::LowPower,
+            compatible_surface: Some(&surface),
+            force_fallback_adapter: false,
+        };
+
+        let adapter = instance.request_adapter(&options).await;
+
+        let adapter = match adapter {
+            Some(adapter) => adapter,
+            None => instance
+                .request_adapter(&wgpu::RequestAdapterOptions {
+                    compatible_surface: Some(&surface),
+                    ..wgpu::RequestAdapterOptions::default()
+                })
+                .await
+                .ok_or(Error::AdapterNotFound)?,
+        };
+        let (device, queue) = adapter
+            .request_device(
+                &wgpu::DeviceDescriptor {
+                    features: wgpu::Features::empty(),
+                    // Just in case I want wasm support later
+                    limits: if cfg!(target_arch = "wasm32") {
+                        wgpu::Limits::downlevel_webgl2_defaults()
+                    } else {
+                        wgpu::Limits::default()
+                    },
+                    label: None,
+                },
+                None,
+            )
+            .await?;
+
+        let surface_caps = surface.get_capabilities(&adapter);
+        // Both are empty if the adapter cannot present to the surface.
+        let (Some(&first_format), Some(&alpha_mode)) = (
+            surface_caps.formats.first(),
+            surface_caps.alpha_modes.first(),
+        ) else {
+            return Err(Error::AdapterNotFound);
+        };
+
+        let surface_format = surface_caps
+            .formats
+            .iter()
+            .copied()
+            .find(|f| f.is_srgb())
+            .unwrap_or(first_format);
+
+        let config = wgpu::SurfaceConfiguration {
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            format: surface_format,
+            width: size.width.max(1),
+            height: size.height.max(1),
+            present_mode: wgpu::PresentMode::Fifo,
+            alpha_mode,
+            view_formats: vec![],
+        };
+
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Shader"),
+            source: wgpu::ShaderSource::Wgsl(SHADER.into()),
+        });
+
+        let vertex_buffer = Self::create_buffer(
+            &device,
+            "Vertex Buffer",
+            1024 * std::mem::size_of::<Vertex>() as u64,
+            wgpu::BufferUsages::VERTEX,
+        );
+        let index_buffer = Self::create_buffer(
+            &device,
+            "Index Buffer",
+            1024 * std::mem::size_of::<u32>() as u64,
+            wgpu::BufferUsages::INDEX,
+        );
+
+        let window_size_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Window Size Buffer"),
+            contents: bytemuck::cast_slice(&[WindowSize {
+                size: [config.width as f32, config.height as f32],
+            }]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let window_bind_layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: Some("Bind Group Layout"),
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::VERTEX,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+        });
+
+        let window_bind_group = device.create_bind_group(&BindGroupDescriptor {
+            label: Some("Bind Group"),
+            layout: &window_bind_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::Buffer(
+                    window_size_buffer.as_entire_buffer_binding(),
+                ),
+            }],
+        });
+
+        let atlas_bind_layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: Some("Atlas Bind Group Layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
+        // Glyphs are placed on whole pixels, so they are sampled unfiltered.
+        let atlas_sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Atlas Sampler"),
+            ..wgpu::SamplerDescriptor::default()
+        });
+        let atlas = GlyphAtlas::default();
+        let atlas_texture = Self::create_atlas_texture(&device, atlas.width(), atlas.height());
+        let atlas_bind_group = Self::create_atlas_bind_group(
+            &device,
+            &atlas_bind_layout,
+            &atlas_texture,
+            &atlas_sampler,
+        );
+
+        let render_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Render Pipeline Layout"),
+                bind_group_layouts: &[&window_bind_layout, &atlas_bind_layout],
+                push_constant_ranges: &[],
+            });
+
+        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Render Pipeline"),
+            layout: Some(&render_pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: "vs_main",
+                buffers: &[Vertex::desc()],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: "fs_main",
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: config.format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                // Tessellated outlines do not keep a consistent winding.
+                cull_mode: None,
+                polygon_mode: wgpu::PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState {
+                count: 1,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+        });
+
+        surface.configure(&device, &config);
+
+        Ok(Self {
+            tessellator: Tessellator {
+                linear: surface_format.is_srgb(),
+                atlas,
+                ..Tessellator::default()
+            },
+            atlas_bind_layout,
+            atlas_sampler,
+            atlas_texture,
+            atlas_bind_group,
+            atlas_version: None,
+            surface,
+            device,
+            queue,
+            config,
+            render_pipeline,
+            vertex_buffer,
+            index_buffer,
+            window_size_buffer,
+            window_bind_group,
+            clear_color: Color::WHITE,
+            _window: window,
+        })
+    }
+
+    pub fn size(&self) -> (u32, u32) {
+        (self.config.width, self.config.height)
+    }
+
+    pub fn resize(&mut self, width: u32, height: u32) {
+        if width == 0 || height == 0 {
+            return;
+        }
+        self.config.width = width;
+        self.config.height = height;
+        self.surface.configure(&self.device, &self.config);
+    }
+
+    /// Copies the tessellated geometry to the GPU, growing the buffers to the
+    /// next power of two whenever they are too small.
+    fn upload(&mut self) {
+        let vertices: &[u8] = bytemuck::cast_slice(&self.tessellator.vertices);
+        let indices: &[u8] = bytemuck::cast_slice(&self.tessellator.indices);
+
+        if vertices.len() as u64 > self.vertex_buffer.size() {
+            self.vertex_buffer = Self::create_buffer(
+                &self.device,
+                "Vertex Buffer",
+                (vertices.len() as u64).next_power_of_two(),
+                wgpu::BufferUsages::VERTEX,
+            );
+        }
+        if indices.len() as u64 > self.index_buffer.size() {
+            self.index_buffer = Self::create_buffer(
+                &self.device,
+                "Index Buffer",
+                (indices.len() as u64).next_power_of_two(),
+                wgpu::BufferUsages::INDEX,
+            );
+        }
+
+        let atlas = &self.tessellator.atlas;
+        if self.atlas_version != Some(atlas.version()) {
+            let size = self.atlas_texture.size();
+            if (size.width, size.height) != (atlas.width(), atlas.height()) {
+                self.atlas_texture =
+                    Self::create_atlas_texture(&self.device, atlas.width(), atlas.height());
+                self.atlas_bind_group = Self::create_atlas_bind_group(
+                    &self.device,
+                    &self.atlas_bind_layout,
+                    &self.atlas_texture,
+                    &self.atlas_sampler,
+                );
+            }
+            self.queue.write_texture(
+                self.atlas_texture.as_image_copy(),
+                atlas.pixels(),
+                wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(atlas.width()),
+                    rows_per_image: None,
+                },
+                self.atlas_texture.size(),
+            );
+            self.atlas_version = Some(atlas.version());
+        }
+
+        self.queue.write_buffer(&self.vertex_buffer, 0, vertices);
+        self.queue.write_buffer(&self.index_buffer, 0, indices);
+        self.queue.write_buffer(
+            &self.window_size_buffer,
+            0,
+            bytemuck::cast_slice(&[WindowSize {
+                size: [self.config.width as f32, self.config.height as f32],
+            }]),
+        );
+    }
+
+    fn create_atlas_texture(device: &wgpu::Device, width: u32, height: u32) -> wgpu::Texture {
+        device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Glyph Atlas"),
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::R8Unorm,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        })
+    }
+
+    fn create_atlas_bind_group(
+        device: &wgpu::Device,
+        layout: &wgpu::BindGroupLayout,
+        texture: &wgpu::Texture,
+        sampler: &wgpu::Sampler,
+    ) -> wgpu::BindGroup {
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        device.create_bind_group(&BindGroupDescriptor {
+            label: Some("Atlas Bind Group"),
+            layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(sampler),
+                },
+            ],
+        })
+    }
+
+    fn create_buffer(
+        device: &wgpu::Device,
+        label: &str,
+        size: u64,
+        usage: wgpu::BufferUsages,
+    ) -> wgpu::Buffer {
+        device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some(label),
+            size,
+            usage: usage | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        })
+    }
+}
+
+impl Renderer for WgpuRenderer {
+    fn clear(&mut self, color: Color) {
+        self.clear_color = color;
+    }
+
+    /// Draws the display list and presents the frame, reporting surface
+    /// errors to the caller.
+    fn try_render(&mut self, list: &DisplayList) -> Result<()> {
+        let (width, height) = (self.config.width as f32, self.config.height as f32);
+        let generation = self.tessellator.atlas.generation();
+        self.tessellator.tessellate(list, width, height);
+        if self.tessellator.atlas.generation() != generation {
+            // The atlas filled up and dropped glyphs placed earlier in the
+            // frame, which now go in again next to the later ones.
+            self.tessellator.tessellate(list, width, height);
+        }
+        if self.tessellator.skipped_images > 0 {
+            log::warn!(
+                "{} images were not drawn, the wgpu renderer cannot draw images yet",
+                self.tessellator.skipped_images
+            );
+        }
+        self.upload();
+
+        let output = self.surface.get_current_texture()?;
+        let view = output
+            .texture
+            .create_view(&wgpu::TextureViewDescriptor::default());
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("Render Encoder"),
+            });
+        {
+            let [r, g, b, a] = self.tessellator.encode(self.clear_color, 1.0);
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Render Pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color {
+                            r: r as f64,
+                            g: g as f64,
+                            b: b as f64,
+                            a: a as f64,
+                        }),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                occlusion_query_set: None,
+                timestamp_writes: None,
+            });
+
+            render_pass.set_pipeline(&self.render_pipeline);
+            render_pass.set_bind_group(0, &self.window_bind_group, &[]);
+            render_pass.set_bind_group(1, &self.atlas_bind_group, &[]);
+            render_pass.set_viewport(0.0, 0.0, width, height, 0.0, 1.0);
+            render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+            render_pass.set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+            for batch in &self.tessellator.batches {
+                let Some(clip) = batch.clip.intersect(&Rect::new(0.0, 0.0, width, height)) else {
+                    continue;
+                };
+                let x = clip.x.floor() as u32;
+                let y = clip.y.floor() as u32;
+                let w = (clip.right().ceil() as u32).min(self.config.width) - x;
+                let h = (clip.bottom().ceil() as u32).min(self.config.height) - y;
+                if w == 0 || h == 0 {
+                    continue;
+                }
+                render_pass.set_scissor_rect(x, y, w, h);
+                render_pass.draw_indexed(batch.indices.clone(), 0, 0..1);
+            }
+        }
+
+        self.queue.submit(std::iter::once(encoder.finish()));
+        output.present();
+
+        Ok(())
+    }
+
+    /// Reconfigures a lost or outdated surface for the next frame, and logs
+    /// any other error.
+    fn render(&mut self, list: &DisplayList) {
+        match self.try_render(list) {
+            Ok(_) => {}
+            Err(Error::Surface(wgpu::SurfaceError::Lost | wgpu::SurfaceError::Outdated)) => {
+                self.surface.configure(&self.device, &self.config)
+            }
+            Err(e) => log::error!("{}", e),
+        }
+    }
+}
@@ -1,2 +1,6 @@
 pub mod style;
+pub mod stylesheet;
+pub mod theme;
 pub use style::*;
+pub use stylesheet::*;
+pub use theme::*;
@@ -1,3 +1,238 @@
-pub fn sty() -> i32 {
-    32
+use crate::layout::Constraints;
+use crate::renderer::DisplayList;
+use crate::text::FontWeight;
+use crate::utils::{Color, Rect};
+
+/// Distances along the four sides of a box, for margins and padding.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Edges {
+    pub top: f32,
+    pub right: f32,
+    pub bottom: f32,
+    pub left: f32,
+}
+
+impl Edges {
+    pub const ZERO: Edges = Edges::all(0.0);
+
+    pub const fn all(value: f32) -> Self {
+        Self {
+            top: value,
+            right: value,
+            bottom: value,
+            left: value,
+        }
+    }
+
+    /// `vertical` above and below, `horizontal` on the left and right.
+    pub const fn symmetric(vertical: f32, horizontal: f32) -> Self {
+        Self {
+            top: vertical,
+            right: horizontal,
+            bottom: vertical,
+            left: horizontal,
+        }
+    }
+
+    /// The left and right edges together.
+    pub fn horizontal(&self) -> f32 {
+        self.left + self.right
+    }
+
+    /// The top and bottom edges together.
+    pub fn vertical(&self) -> f32 {
+        self.top + self.bottom
+    }
+
+    /// `rect` with the edges taken off its sides, never smaller than nothing.
+    pub fn inset(&self, rect: Rect) -> Rect {
+        Rect::new(
+            rect.x + self.left,
+            rect.y + self.top,
+            (rect.width - self.horizontal()).max(0.0),
+            (rect.height - self.vertical()).max(0.0),
+        )
+    }
+}
+
+/// A stroke along the inside of a widget's box.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Border {
+    pub width: f32,
+    pub color: Color,
+}
+
+/// A shadow cast by a widget's box, offset from it and faded out over `blur`
+/// pixels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Shadow {
+    pub offset_x: f32,
+    pub offset_y: f32,
+    pub blur: f32,
+    pub color: Color,
+}
+
+impl Shadow {
+    /// The number of rectangles a blurred shadow is drawn with.
+    const STEPS: usize = 8;
+}
+
+/// How a widget looks: its box, text and effects.
+///
+/// A widget's box is the area it is laid out in less its margin; its content
+/// is the box less the padding. What is left unset is taken from the
+/// widget's defaults, which come from the current [`Theme`](super::Theme).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Style {
+    /// The color of text.
+    pub color: Option<Color>,
+    pub background: Option<Color>,
+    pub border: Option<Border>,
+    /// The radius of the corners of the background, border and shadow.
+    pub corner_radius: Option<f32>,
+    /// Space between the border and the content.
+    pub padding: Edges,
+    /// Space around the box, kept free of the widget and of its neighbours.
+    pub margin: Edges,
+    /// A family added to the [`FontBook`](crate::text::FontBook).
+    pub font_family: Option<String>,
+    /// The size of the font in pixels.
+    pub font_size: Option<f32>,
+    pub font_weight: Option<FontWeight>,
+    /// The opacity of the whole widget, children included, from 0 to 1.
+    pub opacity: f32,
+    pub shadow: Option<Shadow>,
+}
+
+impl Style {
+    /// Nothing drawn and no space taken, the style of unstyled widgets.
+    pub const DEFAULT: Style = Style {
+        color: None,
+        background: None,
+        border: None,
+        corner_radius: None,
+        padding: Edges::ZERO,
+        margin: Edges::ZERO,
+        font_family: None,
+        font_size: None,
+        font_weight: None,
+        opacity: 1.0,
+        shadow: None,
+    };
+
+    /// `self`, with whatever it leaves unset taken from `defaults`. Spacing
+    /// and opacity are always set, and kept.
+    pub fn or(&self, defaults: &Style) -> Style {
+        Style {
+            color: self.color.or(defaults.color),
+            background: self.background.or(defaults.background),
+            border: self.border.or(defaults.border),
+            corner_radius: self.corner_radius.or(defaults.corner_radius),
+            padding: self.padding,
+            margin: self.margin,
+            font_family: self
+                .font_family
+                .clone()
+                .or_else(|| defaults.font_family.clone()),
+            font_size: self.font_size.or(defaults.font_size),
+            font_weight: self.font_weight.or(defaults.font_weight),
+            opacity: self.opacity,
+            shadow: self.shadow.or(defaults.shadow),
+        }
+    }
+
+    fn radius(&self) -> f32 {
+        self.corner_radius.unwrap_or(0.0)
+    }
+
+    /// The box within the `bounds` a widget was laid out in.
+    pub fn border_rect(&self, bounds: Rect) -> Rect {
+        self.margin.inset(bounds)
+    }
+
+    /// Where the content goes within the `bounds` a widget was laid out in.
+    pub fn content_rect(&self, bounds: Rect) -> Rect {
+        self.padding.inset(self.margin.inset(bounds))
+    }
+
+    /// The space left for the content within `constraints`.
+    pub fn content_constraints(&self, constraints: Constraints) -> Constraints {
+        constraints.deflate(
+            self.margin.horizontal() + self.padding.horizontal(),
+            self.margin.vertical() + self.padding.vertical(),
+        )
+    }
+
+    /// Paints the box of a widget whose border box is `rect`, as arranged,
+    /// then its content through `content`, with the widget's opacity applied
+    /// to both.
+    pub fn paint(
+        &self,
+        rect: Rect,
+        list: &mut DisplayList,
+        content: impl FnOnce(Rect, &mut DisplayList),
+    ) {
+        self.with_opacity(list, |list| {
+            self.paint_shadow(rect, list);
+            if let Some(color) = self.background {
+                self.paint_background(rect, color, list);
+            }
+            self.paint_border(rect, list);
+            content(self.padding.inset(rect), list);
+        });
+    }
+
+    /// Draws what `paint` draws on a layer of the style's opacity, unless it
+    /// is opaque.
+    pub fn with_opacity(&self, list: &mut DisplayList, paint: impl FnOnce(&mut DisplayList)) {
+        if self.opacity < 1.0 {
+            list.with_layer(self.opacity.max(0.0), paint);
+        } else {
+            paint(list);
+        }
+    }
+
+    pub fn paint_shadow(&self, rect: Rect, list: &mut DisplayList) {
+        let Some(shadow) = self.shadow else {
+            return;
+        };
+        let rect = Rect::new(
+            rect.x + shadow.offset_x,
+            rect.y + shadow.offset_y,
+            rect.width,
+            rect.height,
+        );
+        if shadow.blur <= 0.0 {
+            return list.rounded_rect(rect, self.radius(), shadow.color);
+        }
+        // Nested rectangles, each adding to the darkness towards the middle.
+        let color = shadow
+            .color
+            .with_alpha(shadow.color.a / Shadow::STEPS as f32);
+        for step in 0..Shadow::STEPS {
+            let spread = shadow.blur * (0.5 - step as f32 / Shadow::STEPS as f32);
+            let rect = Edges::all(-spread).inset(rect);
+            list.rounded_rect(rect, (self.radius() + spread).max(0.0), color);
+        }
+    }
+
+    pub fn paint_background(&self, rect: Rect, color: Color, list: &mut DisplayList) {
+        if self.radius() > 0.0 {
+            list.rounded_rect(rect, self.radius(), color);
+        } else {
+            list.rect(rect, color);
+        }
+    }
+
+    pub fn paint_border(&self, rect: Rect, list: &mut DisplayList) {
+        if let Some(border) = self.border.filter(|border| border.width > 0.0) {
+            list.border(rect, border.width, self.radius(), border.color);
+        }
+    }
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
 }
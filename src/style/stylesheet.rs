@@ -0,0 +1,694 @@
+use std::path::Path;
+
+use super::{Border, Edges, Shadow, Style, Theme};
+use crate::text::FontWeight;
+use crate::utils::Color;
+use crate::widget::Widget;
+
+/// A state a widget can be selected by, such as `:hover`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PseudoClass {
+    Hover,
+    Pressed,
+    Focus,
+    Disabled,
+}
+
+/// The pseudo-classes a widget currently has.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PseudoClasses {
+    pub hover: bool,
+    pub pressed: bool,
+    pub focus: bool,
+    pub disabled: bool,
+}
+
+impl PseudoClasses {
+    pub fn contains(&self, class: PseudoClass) -> bool {
+        match class {
+            PseudoClass::Hover => self.hover,
+            PseudoClass::Pressed => self.pressed,
+            PseudoClass::Focus => self.focus,
+            PseudoClass::Disabled => self.disabled,
+        }
+    }
+}
+
+/// What selectors match of a widget.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Element {
+    pub type_name: &'static str,
+    pub id: Option<String>,
+    pub classes: Vec<String>,
+    pub state: PseudoClasses,
+}
+
+impl Element {
+    pub fn of(widget: &dyn Widget) -> Self {
+        Self {
+            type_name: widget.type_name(),
+            id: widget.id().map(str::to_string),
+            classes: widget.classes().to_vec(),
+            state: widget.pseudo_classes(),
+        }
+    }
+}
+
+/// A selector for a single widget, such as `Button.primary:hover` or `#save`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Compound {
+    /// `None` for `*` or when only classes, ids or states are given.
+    pub type_name: Option<String>,
+    pub id: Option<String>,
+    pub classes: Vec<String>,
+    pub pseudo_classes: Vec<PseudoClass>,
+}
+
+impl Compound {
+    pub fn matches(&self, element: &Element) -> bool {
+        self.type_name
+            .as_ref()
+            .is_none_or(|name| name == element.type_name)
+            && self
+                .id
+                .as_ref()
+                .is_none_or(|id| element.id.as_ref() == Some(id))
+            && self
+                .classes
+                .iter()
+                .all(|class| element.classes.contains(class))
+            && self
+                .pseudo_classes
+                .iter()
+                .all(|&class| element.state.contains(class))
+    }
+}
+
+/// Compound selectors separated by descendant combinators, the last one
+/// selecting the widget and the others any of its ancestors, in order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Selector {
+    pub compounds: Vec<Compound>,
+}
+
+impl Selector {
+    /// The number of ids, of classes and pseudo-classes, and of types, which
+    /// rank selectors in that order.
+    pub fn specificity(&self) -> (usize, usize, usize) {
+        self.compounds
+            .iter()
+            .fold((0, 0, 0), |(ids, classes, types), compound| {
+                (
+                    ids + compound.id.is_some() as usize,
+                    classes + compound.classes.len() + compound.pseudo_classes.len(),
+                    types + compound.type_name.is_some() as usize,
+                )
+            })
+    }
+
+    /// Whether the selector matches `element`, whose ancestors are given from
+    /// the root down.
+    pub fn matches(&self, element: &Element, ancestors: &[Element]) -> bool {
+        let Some((last, rest)) = self.compounds.split_last() else {
+            return false;
+        };
+        if !last.matches(element) {
+            return false;
+        }
+        // Matching each compound to the nearest ancestor never misses a match.
+        let mut ancestors = ancestors.iter().rev();
+        rest.iter()
+            .rev()
+            .all(|compound| ancestors.any(|ancestor| compound.matches(ancestor)))
+    }
+}
+
+/// The properties set by a rule, each left unset when not declared.
+///
+/// The sides of `padding` and `margin` go top, right, bottom, left.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Declarations {
+    pub color: Option<Color>,
+    pub background: Option<Color>,
+    pub border_width: Option<f32>,
+    pub border_color: Option<Color>,
+    pub corner_radius: Option<f32>,
+    pub padding: [Option<f32>; 4],
+    pub margin: [Option<f32>; 4],
+    pub font_family: Option<String>,
+    pub font_size: Option<f32>,
+    pub font_weight: Option<FontWeight>,
+    pub opacity: Option<f32>,
+    /// `Some(None)` for `box-shadow: none`.
+    pub shadow: Option<Option<Shadow>>,
+}
+
+impl Declarations {
+    /// Overrides the properties of `style` that are declared.
+    pub fn apply(&self, style: &mut Style) {
+        fn sides(edges: &mut Edges, sides: &[Option<f32>; 4]) {
+            let [top, right, bottom, left] = *sides;
+            edges.top = top.unwrap_or(edges.top);
+            edges.right = right.unwrap_or(edges.right);
+            edges.bottom = bottom.unwrap_or(edges.bottom);
+            edges.left = left.unwrap_or(edges.left);
+        }
+        style.color = self.color.or(style.color);
+        style.background = self.background.or(style.background);
+        if self.border_width.is_some() || self.border_color.is_some() {
+            let border = style.border.unwrap_or(Border {
+                width: 0.0,
+                color: Theme::current().palette.border,
+            });
+            style.border = Some(Border {
+                width: self.border_width.unwrap_or(border.width),
+                color: self.border_color.unwrap_or(border.color),
+            });
+        }
+        style.corner_radius = self.corner_radius.or(style.corner_radius);
+        sides(&mut style.padding, &self.padding);
+        sides(&mut style.margin, &self.margin);
+        if let Some(family) = &self.font_family {
+            style.font_family = Some(family.clone());
+        }
+        style.font_size = self.font_size.or(style.font_size);
+        style.font_weight = self.font_weight.or(style.font_weight);
+        style.opacity = self.opacity.unwrap_or(style.opacity);
+        if let Some(shadow) = self.shadow {
+            style.shadow = shadow;
+        }
+    }
+}
+
+/// Declarations for the widgets matching any of the selectors.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rule {
+    pub selectors: Vec<Selector>,
+    pub declarations: Declarations,
+}
+
+/// Why a stylesheet could not be loaded.
+#[derive(Debug)]
+pub enum StylesheetError {
+    Io(std::io::Error),
+    /// The source is not valid, at the given line counting from 1.
+    Parse {
+        line: usize,
+        message: String,
+    },
+}
+
+impl std::fmt::Display for StylesheetError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StylesheetError::Io(error) => write!(f, "could not read the stylesheet: {}", error),
+            StylesheetError::Parse { line, message } => write!(f, "line {}: {}", line, message),
+        }
+    }
+}
+
+impl std::error::Error for StylesheetError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            StylesheetError::Io(error) => Some(error),
+            StylesheetError::Parse { .. } => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for StylesheetError {
+    fn from(error: std::io::Error) -> Self {
+        StylesheetError::Io(error)
+    }
+}
+
+/// Rules written in a subset of CSS, styling widgets by their type, class,
+/// id and state:
+///
+/// ```css
+/// Button { background: #3366cc; color: white; border-radius: 6px }
+/// .toolbar Button:hover { background: rgba(0, 0, 0, 0.1) }
+/// #save { font-weight: bold; padding: 4px 12px }
+/// ```
+///
+/// A widget's own [`Style`] is the base the matching rules override, from
+/// the least to the most specific, later rules winning ties.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Stylesheet {
+    rules: Vec<Rule>,
+}
+
+impl Stylesheet {
+    pub fn parse(source: &str) -> Result<Self, StylesheetError> {
+        Parser::new(source).stylesheet()
+    }
+
+    /// Reads and parses the stylesheet at `path`.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, StylesheetError> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+
+    pub fn rules(&self) -> &[Rule] {
+        &self.rules
+    }
+
+    /// The style of `element` with the matching rules applied to `base`, or
+    /// `None` when no rule matches.
+    pub fn compute(&self, base: &Style, element: &Element, ancestors: &[Element]) -> Option<Style> {
+        let mut matched: Vec<((usize, usize, usize), usize, &Declarations)> = Vec::new();
+        for (order, rule) in self.rules.iter().enumerate() {
+            let specificity = rule
+                .selectors
+                .iter()
+                .filter(|selector| selector.matches(element, ancestors))
+                .map(Selector::specificity)
+                .max();
+            if let Some(specificity) = specificity {
+                matched.push((specificity, order, &rule.declarations));
+            }
+        }
+        if matched.is_empty() {
+            return None;
+        }
+        matched.sort_by_key(|&(specificity, order, _)| (specificity, order));
+        let mut style = base.clone();
+        for (_, _, declarations) in matched {
+            declarations.apply(&mut style);
+        }
+        Some(style)
+    }
+
+    /// Styles every widget under `root`, itself included, for their current
+    /// state. Applying again after the states changed restyles the widgets.
+    pub fn apply(&self, root: &mut dyn Widget) {
+        self.apply_within(root, &[]);
+    }
+
+    /// Styles every widget under `root` as a descendant of `ancestors`,
+    /// given from the root down.
+    pub fn apply_within(&self, root: &mut dyn Widget, ancestors: &[Element]) {
+        self.visit(root, &mut ancestors.to_vec());
+    }
+
+    fn visit(&self, widget: &mut dyn Widget, ancestors: &mut Vec<Element>) {
+        widget.set_computed_style(None);
+        let element = Element::of(widget);
+        let style = self.compute(widget.style(), &element, ancestors);
+        widget.set_computed_style(style);
+        ancestors.push(element);
+        for child in widget.children_mut() {
+            self.visit(child.as_mut(), ancestors);
+        }
+        ancestors.pop();
+    }
+}
+
+struct Parser<'a> {
+    source: &'a str,
+    /// Byte offset of the next character to read.
+    position: usize,
+}
+
+type ParseResult<T> = Result<T, StylesheetError>;
+
+impl<'a> Parser<'a> {
+    fn new(source: &'a str) -> Self {
+        Self {
+            source,
+            position: 0,
+        }
+    }
+
+    fn error<T>(&self, offset: usize, message: impl Into<String>) -> ParseResult<T> {
+        Err(StylesheetError::Parse {
+            line: self.source[..offset].matches('\n').count() + 1,
+            message: message.into(),
+        })
+    }
+
+    /// Skips whitespace and comments.
+    fn skip(&mut self) -> ParseResult<()> {
+        loop {
+            let rest = &self.source[self.position..];
+            let trimmed = rest.trim_start();
+            self.position += rest.len() - trimmed.len();
+            let Some(comment) = trimmed.strip_prefix("/*") else {
+                return Ok(());
+            };
+            match comment.find("*/") {
+                Some(end) => self.position += end + 4,
+                None => return self.error(self.position, "unclosed comment"),
+            }
+        }
+    }
+
+    /// Reads up to, and past, `delimiter`, or to the end without one,
+    /// returning what was read with its offset. Comments are blanked out,
+    /// keeping the offsets and lines of what follows them.
+    fn until(&mut self, delimiter: Option<char>, what: &str) -> ParseResult<(usize, String)> {
+        let start = self.position;
+        let mut text = String::new();
+        loop {
+            let rest = &self.source[self.position..];
+            if let Some(comment) = rest.strip_prefix("/*") {
+                let Some(end) = comment.find("*/") else {
+                    return self.error(self.position, "unclosed comment");
+                };
+                for c in rest[..end + 4].chars() {
+                    match c {
+                        '\n' => text.push('\n'),
+                        c => text.extend(std::iter::repeat_n(' ', c.len_utf8())),
+                    }
+                }
+                self.position += end + 4;
+                continue;
+            }
+            let Some(c) = rest.chars().next() else {
+                return match delimiter {
+                    Some(delimiter) => {
+                        self.error(start, format!("expected `{}` after {}", delimiter, what))
+                    }
+                    None => Ok((start, text)),
+                };
+            };
+            self.position += c.len_utf8();
+            if Some(c) == delimiter {
+                return Ok((start, text));
+            }
+            if c == '{' || c == '}' {
+                return self.error(self.position - 1, format!("unexpected `{}`", c));
+            }
+            text.push(c);
+        }
+    }
+
+    fn stylesheet(mut self) -> ParseResult<Stylesheet> {
+        let mut rules = Vec::new();
+        loop {
+            self.skip()?;
+            if self.position == self.source.len() {
+                return Ok(Stylesheet { rules });
+            }
+            let (start, selectors) = self.until(Some('{'), "the selectors")?;
+            let selectors = self.selectors(start, &selectors)?;
+            let (start, body) = self.until(Some('}'), "the declarations")?;
+            let mut declarations = Declarations::default();
+            self.each_declaration(start, &body, |name, value| {
+                property(&mut declarations, name, value)
+            })?;
+            rules.push(Rule {
+                selectors,
+                declarations,
+            });
+        }
+    }
+
+    fn selectors(&self, offset: usize, text: &str) -> ParseResult<Vec<Selector>> {
+        text.split(',')
+            .map(|selector| {
+                let compounds = selector
+                    .split_whitespace()
+                    .map(|compound| self.compound(offset, compound))
+                    .collect::<ParseResult<Vec<_>>>()?;
+                if compounds.is_empty() {
+                    return self.error(offset, "expected a selector");
+                }
+                Ok(Selector { compounds })
+            })
+            .collect()
+    }
+
+    fn compound(&self, offset: usize, text: &str) -> ParseResult<Compound> {
+        let is_name = |c: char| c.is_alphanumeric() || c == '-' || c == '_';
+        let mut compound = Compound::default();
+        let mut rest = text;
+        if let Some(after) = rest.strip_prefix('*') {
+            rest = after;
+        } else {
+            let end = rest.find(|c| !is_name(c)).unwrap_or(rest.len());
+            if end > 0 {
+                compound.type_name = Some(rest[..end].to_string());
+            }
+            rest = &rest[end..];
+        }
+        while let Some(prefix) = rest.chars().next() {
+            let after = &rest[prefix.len_utf8()..];
+            let end = after.find(|c| !is_name(c)).unwrap_or(after.len());
+            let name = &after[..end];
+            if name.is_empty() {
+                return self.error(
+                    offset,
+                    format!("unexpected `{}` in selector `{}`", prefix, text),
+                );
+            }
+            match prefix {
+                '.' => compound.classes.push(name.to_string()),
+                '#' => compound.id = Some(name.to_string()),
+                ':' => compound.pseudo_classes.push(match name {
+                    "hover" => PseudoClass::Hover,
+                    "pressed" | "active" => PseudoClass::Pressed,
+                    "focus" => PseudoClass::Focus,
+                    "disabled" => PseudoClass::Disabled,
+                    _ => return self.error(offset, format!("unknown pseudo-class `:{}`", name)),
+                }),
+                _ => {
+                    return self.error(
+                        offset,
+                        format!("unexpected `{}` in selector `{}`", prefix, text),
+                    )
+                }
+            }
+            rest = &after[end..];
+        }
+        Ok(compound)
+    }
+
+    /// Calls `property` with the lowercase name and the value of each
+    /// declaration of `body`, read from `offset`.
+    fn each_declaration(
+        &self,
+        offset: usize,
+        body: &str,
+        mut property: impl FnMut(&str, &str) -> Result<(), String>,
+    ) -> ParseResult<()> {
+        let mut start = 0;
+        for declaration in body.split(';') {
+            let at = offset + start + (declaration.len() - declaration.trim_start().len());
+            start += declaration.len() + 1;
+            if declaration.trim().is_empty() {
+                continue;
+            }
+            let Some((name, value)) = declaration.split_once(':') else {
+                return self.error(at, format!("expected `:` in `{}`", declaration.trim()));
+            };
+            let (name, value) = (name.trim().to_ascii_lowercase(), value.trim());
+            property(&name, value).or_else(|message| self.error(at, message))?;
+        }
+        Ok(())
+    }
+}
+
+/// Calls `property` with the name and value of each declaration of
+/// `source`, a list of declarations outside of any rule, as in a theme file.
+pub(super) fn parse_declarations(
+    source: &str,
+    property: impl FnMut(&str, &str) -> Result<(), String>,
+) -> Result<(), StylesheetError> {
+    let mut parser = Parser::new(source);
+    let (start, body) = parser.until(None, "")?;
+    parser.each_declaration(start, &body, property)
+}
+
+/// Parses the value of the property `name` into `declarations`.
+fn property(declarations: &mut Declarations, name: &str, value: &str) -> Result<(), String> {
+    let side = |sides: &mut [Option<f32>; 4], index: usize| -> Result<(), String> {
+        sides[index] = Some(length(value)?);
+        Ok(())
+    };
+    match name {
+        "color" => declarations.color = Some(color(value)?),
+        "background" | "background-color" => declarations.background = Some(color(value)?),
+        "border" => {
+            let (mut width, mut stroke) = (None, None);
+            for part in split_value(value) {
+                if part == "none" {
+                    width = Some(0.0);
+                } else if let Ok(length) = length(part) {
+                    width = Some(length);
+                } else if part != "solid" {
+                    stroke = Some(color(part)?);
+                }
+            }
+            declarations.border_width = Some(width.unwrap_or(1.0));
+            declarations.border_color = stroke.or(declarations.border_color);
+        }
+        "border-width" => declarations.border_width = Some(length(value)?),
+        "border-color" => declarations.border_color = Some(color(value)?),
+        "border-radius" => declarations.corner_radius = Some(length(value)?),
+        "padding" => declarations.padding = edges(value)?,
+        "padding-top" => side(&mut declarations.padding, 0)?,
+        "padding-right" => side(&mut declarations.padding, 1)?,
+        "padding-bottom" => side(&mut declarations.padding, 2)?,
+        "padding-left" => side(&mut declarations.padding, 3)?,
+        "margin" => declarations.margin = edges(value)?,
+        "margin-top" => side(&mut declarations.margin, 0)?,
+        "margin-right" => side(&mut declarations.margin, 1)?,
+        "margin-bottom" => side(&mut declarations.margin, 2)?,
+        "margin-left" => side(&mut declarations.margin, 3)?,
+        "font-family" => {
+            let family = value.split(',').next().unwrap_or_default().trim();
+            declarations.font_family = Some(family.trim_matches(['"', '\'']).to_string());
+        }
+        "font-size" => declarations.font_size = Some(length(value)?),
+        "font-weight" => {
+            declarations.font_weight = Some(match value {
+                "normal" => FontWeight::NORMAL,
+                "bold" => FontWeight::BOLD,
+                _ => FontWeight(
+                    value
+                        .parse()
+                        .map_err(|_| format!("invalid font weight `{}`", value))?,
+                ),
+            })
+        }
+        "opacity" => {
+            let opacity = match value.strip_suffix('%') {
+                Some(percent) => number(percent)? / 100.0,
+                None => number(value)?,
+            };
+            declarations.opacity = Some(opacity.clamp(0.0, 1.0));
+        }
+        "box-shadow" => declarations.shadow = Some(shadow(value)?),
+        _ => return Err(format!("unknown property `{}`", name)),
+    }
+    Ok(())
+}
+
+/// Splits a value on whitespace outside of parentheses.
+fn split_value(value: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let (mut depth, mut start) = (0, None);
+    for (index, c) in value.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            c if c.is_whitespace() && depth == 0 => {
+                if let Some(start) = start.take() {
+                    parts.push(&value[start..index]);
+                }
+                continue;
+            }
+            _ => {}
+        }
+        start.get_or_insert(index);
+    }
+    if let Some(start) = start {
+        parts.push(&value[start..]);
+    }
+    parts
+}
+
+fn number(value: &str) -> Result<f32, String> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| format!("invalid number `{}`", value.trim()))
+}
+
+/// A length in pixels, with or without the `px` unit.
+pub(super) fn length(value: &str) -> Result<f32, String> {
+    let value = value.trim();
+    number(value.strip_suffix("px").unwrap_or(value))
+        .map_err(|_| format!("invalid length `{}`", value))
+}
+
+/// One to four lengths, for the sides in the order of CSS.
+fn edges(value: &str) -> Result<[Option<f32>; 4], String> {
+    let lengths = split_value(value)
+        .into_iter()
+        .map(length)
+        .collect::<Result<Vec<_>, _>>()?;
+    let [top, right, bottom, left] = match lengths[..] {
+        [all] => [all; 4],
+        [vertical, horizontal] => [vertical, horizontal, vertical, horizontal],
+        [top, horizontal, bottom] => [top, horizontal, bottom, horizontal],
+        [top, right, bottom, left] => [top, right, bottom, left],
+        _ => return Err(format!("expected one to four lengths, found `{}`", value)),
+    };
+    Ok([Some(top), Some(right), Some(bottom), Some(left)])
+}
+
+/// `#rgb`, `#rrggbb`, `#rrggbbaa`, `rgb(r, g, b)`, `rgba(r, g, b, a)` or a
+/// color name.
+pub(super) fn color(value: &str) -> Result<Color, String> {
+    let invalid = || format!("invalid color `{}`", value);
+    let value = value.trim();
+    if let Some(hex) = value.strip_prefix('#') {
+        let digits: Vec<u8> = hex
+            .chars()
+            .map(|c| c.to_digit(16).map(|digit| digit as u8))
+            .collect::<Option<_>>()
+            .ok_or_else(invalid)?;
+        let [r, g, b, a] = match digits[..] {
+            [r, g, b] => [r * 17, g * 17, b * 17, 0xFF],
+            [r1, r2, g1, g2, b1, b2] => [r1 * 16 + r2, g1 * 16 + g2, b1 * 16 + b2, 0xFF],
+            [r1, r2, g1, g2, b1, b2, a1, a2] => {
+                [r1 * 16 + r2, g1 * 16 + g2, b1 * 16 + b2, a1 * 16 + a2]
+            }
+            _ => return Err(invalid()),
+        };
+        return Ok(Color::from_rgba8(r, g, b, a));
+    }
+    if let Some(arguments) = value
+        .strip_prefix("rgba(")
+        .or_else(|| value.strip_prefix("rgb("))
+        .and_then(|rest| rest.strip_suffix(')'))
+    {
+        let channels = arguments
+            .split(',')
+            .map(number)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| invalid())?;
+        return match channels[..] {
+            [r, g, b] => Ok(Color::rgb(r / 255.0, g / 255.0, b / 255.0)),
+            [r, g, b, a] => Ok(Color::rgba(r / 255.0, g / 255.0, b / 255.0, a)),
+            _ => Err(invalid()),
+        };
+    }
+    match value.to_ascii_lowercase().as_str() {
+        "transparent" => Ok(Color::TRANSPARENT),
+        "black" => Ok(Color::BLACK),
+        "white" => Ok(Color::WHITE),
+        "red" => Ok(Color::rgb(1.0, 0.0, 0.0)),
+        "green" => Ok(Color::from_rgba8(0x00, 0x80, 0x00, 0xFF)),
+        "blue" => Ok(Color::rgb(0.0, 0.0, 1.0)),
+        "gray" | "grey" => Ok(Color::from_rgba8(0x80, 0x80, 0x80, 0xFF)),
+        _ => Err(invalid()),
+    }
+}
+
+/// `none`, or an offset, an optional blur and a color.
+fn shadow(value: &str) -> Result<Option<Shadow>, String> {
+    if value == "none" {
+        return Ok(None);
+    }
+    let parts = split_value(value);
+    let Some((last, lengths)) = parts.split_last() else {
+        return Err("expected a shadow".to_string());
+    };
+    let lengths = lengths
+        .iter()
+        .map(|part| length(part))
+        .collect::<Result<Vec<_>, _>>()?;
+    let (offset_x, offset_y, blur) = match lengths[..] {
+        [x, y] => (x, y, 0.0),
+        [x, y, blur] => (x, y, blur),
+        _ => return Err(format!("expected two or three lengths in `{}`", value)),
+    };
+    Ok(Some(Shadow {
+        offset_x,
+        offset_y,
+        blur,
+        color: color(last)?,
+    }))
+}
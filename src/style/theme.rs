@@ -0,0 +1,265 @@
+use std::{cell::RefCell, path::Path, rc::Rc};
+
+use super::stylesheet::{color, length, parse_declarations};
+use super::StylesheetError;
+use crate::event::window::ColorScheme;
+use crate::utils::Color;
+
+/// The named colors of a [`Theme`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Palette {
+    /// Behind everything, the color windows are cleared to.
+    pub background: Color,
+    /// Raised areas such as cards and popups.
+    pub surface: Color,
+    pub text: Color,
+    pub text_disabled: Color,
+    /// Highlights, such as the ring around the focused widget.
+    pub accent: Color,
+    pub border: Color,
+    /// The background of controls such as buttons.
+    pub control: Color,
+    pub control_hovered: Color,
+    pub control_pressed: Color,
+    pub control_disabled: Color,
+    pub shadow: Color,
+}
+
+/// Spacing steps in pixels, for margins, padding and gaps.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Spacing {
+    pub xs: f32,
+    pub sm: f32,
+    pub md: f32,
+    pub lg: f32,
+    pub xl: f32,
+}
+
+/// Font sizes in pixels, from the smallest print to headings.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Typography {
+    /// A family added to the [`FontBook`](crate::text::FontBook), the bitmap
+    /// font if empty.
+    pub font_family: String,
+    pub small: f32,
+    pub body: f32,
+    pub title: f32,
+    pub heading: f32,
+}
+
+/// Corner radii in pixels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Radii {
+    /// For controls such as buttons.
+    pub small: f32,
+    pub medium: f32,
+    pub large: f32,
+}
+
+/// The design tokens widgets take the parts of their look that their
+/// [`Style`](super::Style) leaves unset from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    pub color_scheme: ColorScheme,
+    pub palette: Palette,
+    pub spacing: Spacing,
+    pub typography: Typography,
+    pub radii: Radii,
+}
+
+thread_local! {
+    static THEME: RefCell<Rc<Theme>> = RefCell::new(Rc::new(Theme::light()));
+}
+
+impl Theme {
+    pub fn light() -> Self {
+        let gray = |value| Color::from_rgba8(value, value, value, 0xFF);
+        Self {
+            color_scheme: ColorScheme::Light,
+            palette: Palette {
+                background: Color::WHITE,
+                surface: gray(0xF5),
+                text: Color::BLACK,
+                text_disabled: gray(0xA0),
+                accent: Color::from_rgba8(0x33, 0x66, 0xCC, 0xFF),
+                border: gray(0xA0),
+                control: gray(0xE0),
+                control_hovered: gray(0xD0),
+                control_pressed: gray(0xB8),
+                control_disabled: gray(0xF0),
+                shadow: Color::BLACK.with_alpha(0.25),
+            },
+            spacing: Spacing::default(),
+            typography: Typography::default(),
+            radii: Radii::default(),
+        }
+    }
+
+    pub fn dark() -> Self {
+        let gray = |value| Color::from_rgba8(value, value, value, 0xFF);
+        Self {
+            color_scheme: ColorScheme::Dark,
+            palette: Palette {
+                background: gray(0x1E),
+                surface: gray(0x2A),
+                text: gray(0xE6),
+                text_disabled: gray(0x6E),
+                accent: Color::from_rgba8(0x5C, 0x8D, 0xF6, 0xFF),
+                border: gray(0x5A),
+                control: gray(0x3A),
+                control_hovered: gray(0x47),
+                control_pressed: gray(0x55),
+                control_disabled: gray(0x2A),
+                shadow: Color::BLACK.with_alpha(0.5),
+            },
+            spacing: Spacing::default(),
+            typography: Typography::default(),
+            radii: Radii::default(),
+        }
+    }
+
+    /// The built-in theme for `scheme`.
+    pub fn for_scheme(scheme: ColorScheme) -> Self {
+        match scheme {
+            ColorScheme::Light => Self::light(),
+            ColorScheme::Dark => Self::dark(),
+        }
+    }
+
+    /// Reads a theme from declarations of its tokens, such as
+    ///
+    /// ```css
+    /// color-scheme: dark;
+    /// accent: #e0a030;
+    /// spacing-md: 10px;
+    /// font-size-body: 15px;
+    /// radius-small: 2px;
+    /// ```
+    ///
+    /// The built-in theme of the color scheme, light by default, gives the
+    /// tokens that are not declared.
+    pub fn parse(source: &str) -> Result<Self, StylesheetError> {
+        let mut scheme = ColorScheme::Light;
+        parse_declarations(source, |name, value| {
+            if name == "color-scheme" {
+                scheme = match value {
+                    "light" => ColorScheme::Light,
+                    "dark" => ColorScheme::Dark,
+                    _ => return Err(format!("unknown color scheme `{}`", value)),
+                };
+            }
+            Ok(())
+        })?;
+        let mut theme = Self::for_scheme(scheme);
+        parse_declarations(source, |name, value| theme.token(name, value))?;
+        Ok(theme)
+    }
+
+    /// Reads and parses the theme file at `path`.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, StylesheetError> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+
+    fn token(&mut self, name: &str, value: &str) -> Result<(), String> {
+        if let Some(token) = self.color_token(name) {
+            *token = color(value)?;
+        } else if let Some(token) = self.length_token(name) {
+            *token = length(value)?;
+        } else if name == "font-family" {
+            self.typography.font_family = value.trim_matches(['"', '\'']).to_string();
+        } else if name != "color-scheme" {
+            return Err(format!("unknown theme token `{}`", name));
+        }
+        Ok(())
+    }
+
+    fn color_token(&mut self, name: &str) -> Option<&mut Color> {
+        let palette = &mut self.palette;
+        Some(match name {
+            "background" => &mut palette.background,
+            "surface" => &mut palette.surface,
+            "text" => &mut palette.text,
+            "text-disabled" => &mut palette.text_disabled,
+            "accent" => &mut palette.accent,
+            "border" => &mut palette.border,
+            "control" => &mut palette.control,
+            "control-hovered" => &mut palette.control_hovered,
+            "control-pressed" => &mut palette.control_pressed,
+            "control-disabled" => &mut palette.control_disabled,
+            "shadow" => &mut palette.shadow,
+            _ => return None,
+        })
+    }
+
+    fn length_token(&mut self, name: &str) -> Option<&mut f32> {
+        Some(match name {
+            "spacing-xs" => &mut self.spacing.xs,
+            "spacing-sm" => &mut self.spacing.sm,
+            "spacing-md" => &mut self.spacing.md,
+            "spacing-lg" => &mut self.spacing.lg,
+            "spacing-xl" => &mut self.spacing.xl,
+            "font-size-small" => &mut self.typography.small,
+            "font-size-body" => &mut self.typography.body,
+            "font-size-title" => &mut self.typography.title,
+            "font-size-heading" => &mut self.typography.heading,
+            "radius-small" => &mut self.radii.small,
+            "radius-medium" => &mut self.radii.medium,
+            "radius-large" => &mut self.radii.large,
+            _ => return None,
+        })
+    }
+
+    /// The theme widgets painted on this thread are styled with, the light
+    /// theme unless another was set.
+    pub fn current() -> Rc<Theme> {
+        THEME.with(|theme| theme.borrow().clone())
+    }
+
+    /// Styles the widgets painted on this thread from now on with `theme`.
+    /// Windows only pick it up once they are painted again, which
+    /// [`Application::set_theme`](crate::platform::Application::set_theme)
+    /// takes care of.
+    pub fn set(theme: Theme) {
+        THEME.with(|current| *current.borrow_mut() = Rc::new(theme));
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::light()
+    }
+}
+
+impl Default for Spacing {
+    fn default() -> Self {
+        Self {
+            xs: 2.0,
+            sm: 4.0,
+            md: 8.0,
+            lg: 16.0,
+            xl: 24.0,
+        }
+    }
+}
+
+impl Default for Typography {
+    fn default() -> Self {
+        Self {
+            font_family: String::new(),
+            small: 12.0,
+            body: 16.0,
+            title: 20.0,
+            heading: 28.0,
+        }
+    }
+}
+
+impl Default for Radii {
+    fn default() -> Self {
+        Self {
+            small: 4.0,
+            medium: 8.0,
+            large: 16.0,
+        }
+    }
+}
@@ -1,27 +1,87 @@
 #[cfg(test)]
-
 pub mod tests {
-    use std::process::exit;
-
-    use crate::event::window::WindowEvent;
+    use std::{
+        cell::{Cell, RefCell},
+        rc::Rc,
+        sync::Arc,
+    };
 
-    use crate::platform::{Window, WindowInterface};
+    use crate::event::window::{CloseRequest, ColorScheme, WindowEvent};
+    use crate::event::{
+        dispatch_pointer, focus_order, hit_test, Event, Key, KeyEventKind, Modifiers,
+        PointerButton, PointerEvent, PointerEventKind, PointerRouter, Propagation,
+    };
+    use crate::layout::{
+        flex, AlignItems, Anchor, Constraints, FlexChild, FlexContainer, FlexDirection, FlexWrap,
+        JustifyContent, LayoutNode, Track, Viewport,
+    };
+    use crate::platform::{
+        AppProxy, Application, ApplicationClosed, Window, WindowInterface, WindowKind,
+    };
+    use crate::renderer::{
+        bitmap_font, DisplayList, Image, Primitive, Renderer, SoftwareRenderer, Transform,
+    };
+    use crate::style::{Border, Edges, Shadow, Style, Stylesheet, StylesheetError, Theme};
+    use crate::utils::{Color, Rect};
     use crate::widget::macros::*;
     use crate::widget::*;
-    use crate::{event::Event, window::run};
 
     #[test]
     fn events() {
-        let mut event = Event::<String>::new();
-        event.subscribe(|event| {
-            println!("say once: {}", event);
-        });
+        let event = Event::<String>::new();
+        let log = Rc::new(RefCell::new(Vec::new()));
+
+        let seen = log.clone();
+        let _first =
+            event.subscribe(move |event| seen.borrow_mut().push(format!("first {}", event)));
+        let seen = log.clone();
+        let _second =
+            event.subscribe(move |event| seen.borrow_mut().push(format!("second {}", event)));
 
-        event.subscribe(|event| {
-            println!("say twice: {}", event);
+        event.trigger("a".to_string());
+        event.trigger("b".to_string());
+        // Listeners of the same priority run in the order they subscribed.
+        assert_eq!(
+            *log.borrow(),
+            ["first a", "second a", "first b", "second b"]
+        );
+    }
+
+    #[test]
+    fn event_listeners() {
+        let event = Event::<i32>::new();
+        let log = Rc::new(RefCell::new(Vec::new()));
+
+        let seen = log.clone();
+        let mut total = 0;
+        let sum = event.subscribe(move |value| {
+            total += value;
+            seen.borrow_mut().push(format!("sum {}", total));
+        });
+        let seen = log.clone();
+        let _first = event.subscribe_with_priority(10, move |value| {
+            seen.borrow_mut().push(format!("first {}", value));
+            if value < 0 {
+                Propagation::Stop
+            } else {
+                Propagation::Continue
+            }
         });
+        let seen = log.clone();
+        event
+            .once(move |value| seen.borrow_mut().push(format!("once {}", value)))
+            .detach();
 
-        event.trigger("Gonna be repeated two times total.".to_string());
+        assert_eq!(event.trigger(1), Propagation::Continue);
+        assert_eq!(event.trigger(2), Propagation::Continue);
+        assert_eq!(event.trigger(-1), Propagation::Stop);
+        drop(sum);
+        event.trigger(4);
+        assert_eq!(event.len(), 1);
+        assert_eq!(
+            *log.borrow(),
+            ["first 1", "sum 1", "once 1", "first 2", "sum 3", "first -1", "first 4",]
+        );
     }
 
     #[test]
@@ -46,7 +106,7 @@ pub mod tests {
         use Size::*;
 
         widget!( | widget::Window |
-            
+
             title:  "My Super Cool App",
             width:  Pixel(500.0),
             height: Pixel(500.0),
@@ -65,6 +125,1367 @@ pub mod tests {
                 ),
             ),
         )
-        .render();
+        .render_on(Window::headless("My Super Cool App"))
+        .unwrap();
+    }
+
+    #[test]
+    fn headless_window() {
+        let mut window = Window::headless("hello world");
+        window.set_size(200.0, 300.0);
+
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let log = seen.clone();
+        window.handle_events(move |event| {
+            if let WindowEvent::WindowResize(x, y) = event {
+                log.borrow_mut().push((x, y));
+            }
+        });
+
+        window.inject(WindowEvent::WindowResize(640.0, 480.0));
+        assert_eq!(window.size(), (200.0, 300.0));
+
+        window.pump();
+        assert_eq!(window.size(), (640.0, 480.0));
+        assert_eq!(*seen.borrow(), vec![(640.0, 480.0)]);
+        assert_eq!(window.title(), "hello world");
+
+        window.close();
+        assert!(window.closed() && !window.visible());
+        window.dispatch(WindowEvent::WindowResize(10.0, 10.0));
+        assert_eq!(*seen.borrow(), vec![(640.0, 480.0)]);
+    }
+
+    #[test]
+    fn window_lifecycle_events() {
+        let window = Window::headless("lifecycle");
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let log = seen.clone();
+        window.handle_events(move |event| log.borrow_mut().push(event));
+
+        let events = [
+            WindowEvent::WindowMoved(30.0, 40.0),
+            WindowEvent::WindowFocused,
+            WindowEvent::ScaleFactorChanged(2.0),
+            WindowEvent::WindowMinimized,
+            WindowEvent::WindowRestored,
+            WindowEvent::ThemeChanged(ColorScheme::Dark),
+            WindowEvent::FileHovered("notes.txt".into()),
+            WindowEvent::FileDropped("notes.txt".into()),
+        ];
+        for event in events.clone() {
+            window.inject(event);
+        }
+        window.show();
+
+        let mut expected = vec![WindowEvent::WindowOpened];
+        expected.extend(events);
+        assert_eq!(*seen.borrow(), expected);
+    }
+
+    #[test]
+    fn window_attributes() {
+        let mut window = Window::headless("attributes");
+        window.set_pos(10.0, 20.0);
+        window.set_title("renamed");
+        window.hide();
+        assert_eq!(window.get_pos(), (10.0, 20.0));
+        assert_eq!(window.get_title(), "renamed");
+        assert!(!window.visible());
+
+        let app = widget!(|crate::widget::Window|
+            width: Size::Pixel(100.0),
+            height: Size::Percent(90.0),
+            resizable: false,
+            position: Some((5.0, 5.0)),
+            fullscreen: true,
+            min_size: Some((Size::Pixel(200.0), Size::Pixel(100.0))),
+            max_size: Some((Size::Percent(50.0), Size::Percent(50.0))),
+            decorations: false,
+        );
+        app.configure(&mut window);
+        assert_eq!(window.get_title(), "My Application");
+        assert!(!window.is_resizeable());
+        assert!(window.is_fullscreen());
+        assert!(!window.has_decorations());
+        assert_eq!(window.get_pos(), (5.0, 5.0));
+        assert_eq!(window.min_size(), Some((200.0, 100.0)));
+        assert_eq!(window.max_size(), Some((960.0, 540.0)));
+        assert_eq!(app.resolve_size(window.screen()), (200.0, 540.0));
+
+        window.set_size(2000.0, 50.0);
+        assert_eq!(window.get_size(), (960.0, 100.0));
+    }
+
+    #[test]
+    fn software_renderer() {
+        let pixels = [0, 0, 255, 255].repeat(4);
+        assert!(Image::from_rgba(3, 2, pixels.clone()).is_none());
+        assert!(Image::from_rgba(u32::MAX, u32::MAX, pixels.clone()).is_none());
+        let image = Arc::new(Image::from_rgba(2, 2, pixels).unwrap());
+
+        let mut list = DisplayList::new();
+        list.rect(Rect::new(0.0, 0.0, 10.0, 10.0), Color::rgb(1.0, 0.0, 0.0));
+        list.rounded_rect(Rect::new(20.0, 20.0, 20.0, 20.0), 8.0, Color::BLACK);
+        list.image(image, Rect::new(0.0, 20.0, 8.0, 8.0));
+        list.with_clip(Rect::new(10.0, 0.0, 5.0, 5.0), |list| {
+            list.rect(Rect::new(0.0, 0.0, 40.0, 10.0), Color::BLACK);
+        });
+        list.with_layer(0.5, |list| {
+            list.with_transform(Transform::translate(30.0, 0.0), |list| {
+                list.rect(Rect::new(0.0, 0.0, 10.0, 10.0), Color::BLACK);
+            });
+        });
+
+        let mut renderer = SoftwareRenderer::new(40, 40);
+        renderer.clear(Color::WHITE);
+        renderer.render(&list);
+
+        let frame = renderer.framebuffer();
+        assert_eq!(frame.pixel(5, 5), [255, 0, 0, 255]);
+        assert_eq!(frame.pixel(15, 15), [255, 255, 255, 255]);
+        assert_eq!(frame.pixel(30, 30), [0, 0, 0, 255]);
+        // The rounded corner leaves the very corner pixel untouched.
+        assert_eq!(frame.pixel(20, 20), [255, 255, 255, 255]);
+        assert_eq!(frame.pixel(4, 24), [0, 0, 255, 255]);
+        // Only the clipped part of the black bar is drawn.
+        assert_eq!(frame.pixel(12, 2), [0, 0, 0, 255]);
+        assert_eq!(frame.pixel(12, 7), [255, 255, 255, 255]);
+        assert_eq!(frame.pixel(35, 5), [128, 128, 128, 255]);
+    }
+
+    #[test]
+    fn software_text() {
+        let mut list = DisplayList::new();
+        list.text("Hi", 0.0, 0.0, 7.0, Color::BLACK);
+
+        let mut renderer = SoftwareRenderer::new(64, 16);
+        renderer.clear(Color::WHITE);
+        renderer.render(&list);
+
+        let frame = renderer.framebuffer();
+        // Left stem of the `H`, and the gap between its stems.
+        assert_eq!(frame.pixel(0, 3), [0, 0, 0, 255]);
+        assert_eq!(frame.pixel(2, 0), [255, 255, 255, 255]);
+        assert_eq!(bitmap_font::measure("Hi", 7.0), (11.0, 7.0));
+    }
+
+    #[test]
+    fn widget_display_list() {
+        use Size::*;
+
+        let window = widget!( | widget::Window |
+            width: Pixel(200.0),
+            height: Pixel(100.0),
+            child: Box::new(widget!( | widget::FlexLayout |
+                width: Percent(100.0),
+                height: Percent(100.0),
+                children: widgets![
+                    widget!( | Button | width: Pixel(50.0), height: Pixel(20.0) )
+                ],
+            )),
+        );
+
+        let list = window.display_list();
+        assert_eq!(
+            list.primitives().first(),
+            Some(&Primitive::PushClip(Rect::new(0.0, 0.0, 200.0, 100.0)))
+        );
+        assert!(list.primitives().iter().any(|primitive| matches!(
+            primitive,
+            Primitive::RoundedRect { rect, .. } if *rect == Rect::new(0.0, 0.0, 50.0, 20.0)
+        )));
+    }
+
+    #[test]
+    fn flex_layout() {
+        use Size::*;
+
+        let row = widget!( | FlexLayout |
+            justify_content: JustifyContent::SpaceBetween,
+            align_items: AlignItems::Center,
+            gap: 10.0,
+            children: widgets![
+                widget!( | Button | width: Pixel(50.0), height: Pixel(20.0) ),
+                widget!( | FlexItem |
+                    grow: 1.0,
+                    child: Box::new(widget!( | Button | width: Pixel(50.0), height: Pixel(40.0) )),
+                ),
+                widget!( | Button | width: Percent(10.0), height: Percent(50.0) ),
+            ],
+        );
+
+        let rects = row.compute_layout(Rect::new(10.0, 0.0, 300.0, 100.0));
+        assert_eq!(
+            rects,
+            vec![
+                Rect::new(10.0, 40.0, 50.0, 20.0),
+                Rect::new(70.0, 30.0, 200.0, 40.0),
+                Rect::new(280.0, 25.0, 30.0, 50.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn flex_layout_stretch() {
+        use Size::*;
+
+        let row = widget!( | FlexLayout |
+            children: widgets![
+                widget!( | Button | width: Pixel(50.0), height: Auto ),
+                widget!( | Button | width: Pixel(50.0), height: Pixel(20.0) ),
+                widget!( | FlexItem |
+                    align_self: Some(AlignItems::Start),
+                    child: Box::new(widget!( | Button | width: Pixel(50.0), height: Auto )),
+                ),
+            ],
+        );
+
+        let rects = row.compute_layout(Rect::new(0.0, 0.0, 300.0, 100.0));
+        // Only the child sized by its content fills the line.
+        assert_eq!(rects[0], Rect::new(0.0, 0.0, 50.0, 100.0));
+        assert_eq!(rects[1], Rect::new(50.0, 0.0, 50.0, 20.0));
+        assert!(rects[2].height < 100.0);
+    }
+
+    #[test]
+    fn flex_wrap_and_column() {
+        let container = FlexContainer {
+            wrap: FlexWrap::Wrap,
+            gap: 5.0,
+            ..Default::default()
+        };
+        let child = FlexChild {
+            basis: 40.0,
+            cross: Some(10.0),
+            ..Default::default()
+        };
+        let rects = flex(&container, 100.0, 100.0, &[child; 3]);
+        assert_eq!(rects[1], Rect::new(45.0, 0.0, 40.0, 10.0));
+        assert_eq!(rects[2], Rect::new(0.0, 15.0, 40.0, 10.0));
+
+        let column = FlexContainer {
+            direction: FlexDirection::ColumnReverse,
+            ..Default::default()
+        };
+        let stretched = FlexChild {
+            basis: 30.0,
+            cross: None,
+            shrink: 1.0,
+            ..Default::default()
+        };
+        let rects = flex(&column, 80.0, 40.0, &[stretched; 2]);
+        // Both shrink to fit the column, and stretch across it.
+        assert_eq!(rects[0], Rect::new(0.0, 20.0, 80.0, 20.0));
+        assert_eq!(rects[1], Rect::new(0.0, 0.0, 80.0, 20.0));
+    }
+
+    #[test]
+    fn grid_layout() {
+        use Size::*;
+
+        let dashboard = widget!( | GridLayout |
+            columns: vec![Track::Pixel(100.0), Track::Fraction(1.0), Track::Fraction(2.0)],
+            rows: vec![Track::Auto, Track::Percent(50.0)],
+            column_gap: 10.0,
+            row_gap: 10.0,
+            children: widgets![
+                // Header across the whole first row.
+                widget!( | GridItem |
+                    row: Some(0),
+                    column: Some(0),
+                    column_span: 3,
+                    child: Box::new(widget!( | Label | height: Pixel(30.0) )),
+                ),
+                widget!( | GridItem |
+                    row_span: 2,
+                    child: Box::new(widget!( | Button | )),
+                ),
+                widget!( | Button | ),
+                widget!( | Button | ),
+                widget!( | Button | height: Pixel(20.0) ),
+            ],
+        );
+
+        let rects = dashboard.compute_layout(Rect::new(0.0, 0.0, 420.0, 200.0));
+        assert_eq!(rects[0], Rect::new(0.0, 0.0, 420.0, 30.0));
+        // The sidebar spans the second row and the implicit third one.
+        assert_eq!(rects[1], Rect::new(0.0, 40.0, 100.0, 130.0));
+        assert_eq!(rects[2], Rect::new(110.0, 40.0, 100.0, 100.0));
+        assert_eq!(rects[3], Rect::new(220.0, 40.0, 200.0, 100.0));
+        assert_eq!(rects[4], Rect::new(110.0, 150.0, 100.0, 20.0));
+    }
+
+    #[test]
+    fn stack_layout() {
+        use Size::*;
+
+        let avatar = widget!( | StackLayout |
+            alignment: Anchor::Center,
+            children: widgets![
+                widget!( | Label | width: Percent(100.0), height: Percent(100.0) ),
+                // A badge hanging off the top-right corner.
+                widget!( | StackItem |
+                    anchor: Some(Anchor::TopRight),
+                    offset_x: 4.0,
+                    offset_y: -4.0,
+                    z_index: 1,
+                    child: Box::new(widget!( | Button | width: Pixel(12.0), height: Pixel(12.0) )),
+                ),
+                widget!( | Button | width: Pixel(20.0), height: Pixel(10.0) ),
+            ],
+        );
+
+        let rects = avatar.compute_layout(Rect::new(10.0, 10.0, 40.0, 40.0));
+        assert_eq!(rects[0], Rect::new(10.0, 10.0, 40.0, 40.0));
+        assert_eq!(rects[1], Rect::new(42.0, 6.0, 12.0, 12.0));
+        assert_eq!(rects[2], Rect::new(20.0, 25.0, 20.0, 10.0));
+        assert_eq!(avatar.paint_order(), vec![0, 2, 1]);
+    }
+
+    #[test]
+    fn measure_and_arrange() {
+        use Size::*;
+
+        let label = widget!( | Label | width: Percent(50.0), height: Pixel(300.0) );
+        let (width, height) = label.measure(Constraints {
+            min_width: 120.0,
+            ..Constraints::loose(200.0, 100.0)
+        });
+        assert_eq!((width, height), (120.0, 100.0));
+        assert_eq!(
+            label.measure(Constraints::UNBOUNDED),
+            (0.0, 300.0),
+            "percentages of an unbounded axis are empty"
+        );
+
+        let window = widget!( | crate::widget::Window |
+            width: Pixel(200.0),
+            height: Pixel(100.0),
+            child: Box::new(widget!( | FlexLayout |
+                width: Percent(100.0),
+                height: Percent(50.0),
+                children: widgets![
+                    widget!( | Button | width: Percent(25.0), height: Percent(100.0) ),
+                    widget!( | StackLayout |
+                        width: Pixel(40.0),
+                        height: Pixel(20.0),
+                        alignment: Anchor::BottomRight,
+                        children: widgets![
+                            widget!( | Label | width: Percent(50.0), height: Pixel(4.0) ),
+                        ],
+                    ),
+                ],
+            )),
+        );
+
+        let tree = window.arrange(Rect::new(0.0, 0.0, 200.0, 100.0));
+        let flex = &tree.children[0];
+        assert_eq!(flex.rect, Rect::new(0.0, 0.0, 200.0, 50.0));
+        assert_eq!(
+            flex.children[0],
+            LayoutNode::leaf(Rect::new(0.0, 0.0, 50.0, 50.0))
+        );
+        assert_eq!(flex.children[1].rect, Rect::new(50.0, 0.0, 40.0, 20.0));
+        assert_eq!(
+            flex.children[1].children[0],
+            LayoutNode::leaf(Rect::new(70.0, 16.0, 20.0, 4.0))
+        );
+    }
+
+    /// A leaf counting how often it is measured.
+    #[derive(Debug)]
+    struct Counted {
+        width: Size,
+        height: Size,
+        measured: Rc<Cell<usize>>,
+    }
+
+    impl Widget for Counted {
+        fn _get_mut_size(&mut self) -> (&mut Size, &mut Size) {
+            (&mut self.width, &mut self.height)
+        }
+        fn _get_size(&self) -> (Size, Size) {
+            (self.width.clone(), self.height.clone())
+        }
+        fn render(self) {}
+        fn content_size(&self, _constraints: Constraints) -> (f32, f32) {
+            self.measured.set(self.measured.get() + 1);
+            (10.0, 10.0)
+        }
+    }
+
+    #[test]
+    fn paint_uses_arrangement() {
+        let measured = Rc::new(Cell::new(0));
+        let counted = || Counted {
+            width: Size::Auto,
+            height: Size::Auto,
+            measured: measured.clone(),
+        };
+        let window = widget!( | crate::widget::Window |
+            width: Size::Pixel(100.0),
+            height: Size::Pixel(100.0),
+            child: Box::new(widget!( | FlexLayout |
+                children: vec![
+                    Box::new(counted()) as Box<dyn Widget>,
+                    Box::new(widget!( | GridLayout |
+                        children: vec![Box::new(counted()) as Box<dyn Widget>],
+                    )),
+                    Box::new(widget!( | StackLayout |
+                        children: vec![Box::new(counted()) as Box<dyn Widget>],
+                    )),
+                ],
+            )),
+        );
+        let layout = window.arrange(Rect::new(0.0, 0.0, 100.0, 100.0));
+        assert!(measured.replace(0) > 0);
+        let mut list = DisplayList::new();
+        window.paint(&layout, &mut list);
+        assert_eq!(measured.get(), 0, "painting measures nothing");
+        assert!(!list.primitives().is_empty());
+    }
+
+    #[test]
+    fn size_units() {
+        use crate::layout::with_viewport;
+        use Size::*;
+
+        assert_eq!((Percent(50.0) - Pixel(8.0)).resolve(200.0), 92.0);
+        assert_eq!((Pixel(10.0) + Percent(10.0) * 2.0).resolve(200.0), 50.0);
+        let clamped = Percent(100.0).clamp(Pixel(50.0), Pixel(120.0));
+        assert_eq!(clamped.resolve(20.0), 50.0);
+        assert_eq!(clamped.resolve(500.0), 120.0);
+        assert_eq!(Auto.resolve_with(500.0, 42.0), 42.0);
+
+        let viewport = Viewport {
+            width: 800.0,
+            height: 600.0,
+            scale_factor: 2.0,
+        };
+        with_viewport(viewport, || {
+            assert_eq!(Logical(10.0).resolve(0.0), 20.0);
+            assert_eq!(ViewportWidth(50.0).resolve(0.0), 400.0);
+            assert_eq!(ViewportHeight(10.0).resolve(0.0), 60.0);
+        });
+        assert_eq!(Logical(10.0).resolve(0.0), 10.0);
+
+        let row = widget!( | FlexLayout |
+            width: Percent(100.0),
+            height: Auto,
+            gap: 10.0,
+            children: widgets![
+                widget!( | Button | width: Pixel(50.0), height: Pixel(30.0) ),
+                widget!( | Button | width: Fill(1.0), height: Pixel(20.0) ),
+                widget!( | Button | width: Fill(3.0), height: Pixel(20.0) ),
+            ],
+        );
+        assert_eq!(row.measure(Constraints::loose(300.0, 400.0)).1, 30.0);
+        let rects = row.compute_layout(Rect::new(0.0, 0.0, 270.0, 30.0));
+        assert_eq!(rects[1], Rect::new(60.0, 0.0, 50.0, 20.0));
+        assert_eq!(rects[2], Rect::new(120.0, 0.0, 150.0, 20.0));
+
+        let window = widget!( | crate::widget::Window |
+            width: Percent(50.0),
+            height: Auto,
+            child: Box::new(row),
+        );
+        assert_eq!(window.resolve_size(viewport), (400.0, 30.0));
+    }
+
+    #[test]
+    fn label_text() {
+        use crate::renderer::GlyphAtlas;
+        use crate::text::{layout_text, Font, FontBook, FontWeight, TextStyle};
+
+        // Without fonts, text is set in the bitmap font: 6px per glyph at 7px.
+        let style = TextStyle {
+            font: None,
+            size: 7.0,
+        };
+        let layout = layout_text("hello world\nabcdefghij", &style, Some(40.0));
+        let lines: Vec<_> = layout.lines.iter().map(|line| line.text.as_str()).collect();
+        assert_eq!(lines, ["hello", "world", "abcdef", "ghij"]);
+        assert_eq!((layout.width, layout.height), (36.0, 36.0));
+
+        let label = widget!( | Label | text: "hello world".to_string(), font_size: 7.0 );
+        assert_eq!(label.measure(Constraints::loose(40.0, 100.0)), (30.0, 18.0));
+        let mut list = DisplayList::new();
+        label.paint(&label.arrange(Rect::new(5.0, 5.0, 40.0, 18.0)), &mut list);
+        let runs: Vec<_> = list
+            .primitives()
+            .iter()
+            .filter_map(|primitive| match primitive {
+                Primitive::Glyphs(run) => Some((run.text.as_str(), run.y)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(runs, [("hello", 5.0), ("world", 14.0)]);
+
+        assert!(Font::from_bytes("broken", FontWeight::NORMAL, vec![1, 2, 3]).is_err());
+        let path = "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf";
+        let Ok(font) = Font::load("DejaVu Sans", FontWeight::NORMAL, path) else {
+            return;
+        };
+        let mut book = FontBook::new();
+        book.add(font.clone());
+        assert_eq!(
+            book.find("dejavu sans", FontWeight::BOLD),
+            Some(font.clone())
+        );
+
+        let mut atlas = GlyphAtlas::new(16, 16);
+        let glyph = crate::text::shape(
+            "W",
+            &TextStyle {
+                font: Some(font.clone()),
+                size: 24.0,
+            },
+        )[0];
+        let entry = atlas
+            .glyph(&font, glyph.id, 24.0)
+            .expect("W has an outline");
+        // A 16px atlas grows to fit a 24px glyph.
+        assert!(atlas.width() >= entry.x + entry.width && atlas.height() >= entry.y + entry.height);
+        let version = atlas.version();
+        assert_eq!(atlas.glyph(&font, glyph.id, 24.0), Some(entry));
+        assert_eq!(
+            atlas.version(),
+            version,
+            "cached glyphs are not rasterized again"
+        );
+        // A full atlas starts over instead of growing without bounds.
+        let mut sizes = 8..;
+        while atlas.generation() == 0 {
+            atlas.glyph(&font, glyph.id, sizes.next().unwrap() as f32);
+        }
+        assert!(atlas.width() <= GlyphAtlas::MAX_SIZE && atlas.height() <= GlyphAtlas::MAX_SIZE);
+        assert!(atlas.glyph(&font, glyph.id, 24.0).is_some());
+        assert!(atlas.glyph(&font, glyph.id, 10_000.0).is_none());
+
+        let mut list = DisplayList::new();
+        list.styled_text(
+            "W",
+            0.0,
+            0.0,
+            &TextStyle {
+                font: Some(font),
+                size: 24.0,
+            },
+            Color::BLACK,
+        );
+        let mut renderer = SoftwareRenderer::new(32, 32);
+        renderer.clear(Color::WHITE);
+        renderer.render(&list);
+        let frame = renderer.framebuffer();
+        let inked = (0..32)
+            .flat_map(|y| (0..32).map(move |x| (x, y)))
+            .filter(|&(x, y)| frame.pixel(x, y)[0] < 128)
+            .count();
+        assert!(inked > 20, "the glyph is drawn from the atlas");
+    }
+
+    #[test]
+    fn button_states() {
+        let clicks = Rc::new(RefCell::new(0));
+        let mut button = widget!( | Button | text: "OK".to_string() );
+        let counter = clicks.clone();
+        button
+            .on_click
+            .subscribe(move |_| *counter.borrow_mut() += 1)
+            .detach();
+
+        // Two bitmap glyphs of 16px plus the padding.
+        let scale = 16.0 / 7.0;
+        let text_width = 12.0 * scale;
+        let (width, height) = button.measure(Constraints::UNBOUNDED);
+        assert!((width - (text_width + 24.0)).abs() < 1e-3);
+        assert!((height - (9.0 * scale + 12.0)).abs() < 1e-3);
+
+        button.pointer_enter();
+        button.pointer_down();
+        assert!(button.state().pressed && button.state().hovered);
+        button.pointer_up();
+        assert_eq!(*clicks.borrow(), 1);
+
+        // Dragging off the button cancels the click.
+        button.pointer_down();
+        button.pointer_leave();
+        button.pointer_up();
+        assert_eq!(*clicks.borrow(), 1);
+
+        button.disabled = true;
+        button.pointer_enter();
+        button.pointer_down();
+        button.pointer_up();
+        button.click();
+        assert_eq!(*clicks.borrow(), 1);
+        assert!(!button.state().pressed);
+
+        button.disabled = false;
+        button.set_focused(true);
+        let mut list = DisplayList::new();
+        button.paint(&button.arrange(Rect::new(0.0, 0.0, 60.0, 30.0)), &mut list);
+        assert!(list.primitives().iter().any(|primitive| matches!(
+            primitive,
+            Primitive::Border { width, .. } if *width == 2.0
+        )));
+        assert!(list.primitives().iter().any(|primitive| matches!(
+            primitive,
+            Primitive::Glyphs(run) if run.text == "OK" && (run.x - (60.0 - text_width) / 2.0).abs() < 1e-3
+        )));
+    }
+
+    #[derive(Debug, Default)]
+    struct Probe {
+        width: Size,
+        height: Size,
+        name: &'static str,
+        children: Widgets,
+        stop: bool,
+        log: Rc<RefCell<Vec<String>>>,
+    }
+
+    impl Widget for Probe {
+        fn _get_mut_size(&mut self) -> (&mut Size, &mut Size) {
+            (&mut self.width, &mut self.height)
+        }
+        fn _get_size(&self) -> (Size, Size) {
+            (self.width.clone(), self.height.clone())
+        }
+        fn render(self) {}
+        fn arrange(&self, bounds: Rect) -> LayoutNode {
+            LayoutNode {
+                rect: bounds,
+                children: self
+                    .children
+                    .iter()
+                    .map(|child| {
+                        let (width, height) = child.measure(Constraints::UNBOUNDED);
+                        child.arrange(Rect::new(bounds.x, bounds.y, width, height))
+                    })
+                    .collect(),
+            }
+        }
+        fn children(&self) -> &[Box<dyn Widget>] {
+            &self.children
+        }
+        fn children_mut(&mut self) -> &mut [Box<dyn Widget>] {
+            &mut self.children
+        }
+        fn on_pointer(&mut self, event: &mut PointerEvent, _bounds: Rect) {
+            self.log.borrow_mut().push(format!(
+                "{} {:?} {:?}",
+                self.name,
+                event.phase(),
+                event.kind
+            ));
+            if self.stop {
+                event.stop_propagation();
+            }
+        }
+    }
+
+    #[test]
+    fn pointer_routing() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let probe = |name, size: f32, children| Probe {
+            width: Size::Pixel(size),
+            height: Size::Pixel(size),
+            name,
+            children,
+            log: log.clone(),
+            ..Probe::default()
+        };
+        let inner: Widgets = vec![Box::new(probe("inner", 10.0, Vec::new()))];
+        let mut root = probe("root", 100.0, vec![Box::new(probe("middle", 50.0, inner))]);
+        let layout = root.arrange(Rect::new(0.0, 0.0, 100.0, 100.0));
+
+        assert_eq!(hit_test(&root, &layout, 5.0, 5.0), Some(vec![0, 0]));
+        assert_eq!(hit_test(&root, &layout, 30.0, 30.0), Some(vec![0]));
+        assert_eq!(hit_test(&root, &layout, 150.0, 5.0), None);
+
+        let down = PointerEventKind::Down(PointerButton::Primary);
+        dispatch_pointer(
+            &mut root,
+            &layout,
+            &[0, 0],
+            &mut PointerEvent::new(down, 5.0, 5.0),
+        );
+        assert_eq!(
+            *log.borrow(),
+            [
+                "root Capture Down(Primary)",
+                "middle Capture Down(Primary)",
+                "inner Target Down(Primary)",
+                "middle Bubble Down(Primary)",
+                "root Bubble Down(Primary)",
+            ]
+        );
+
+        log.borrow_mut().clear();
+        root.children[0] = Box::new(Probe {
+            stop: true,
+            ..probe("middle", 50.0, Vec::new())
+        });
+        let layout = root.arrange(Rect::new(0.0, 0.0, 100.0, 100.0));
+        let mut router = PointerRouter::new();
+        router.handle(&mut root, &layout, down, 5.0, 5.0);
+        router.handle(&mut root, &layout, PointerEventKind::Move, 80.0, 80.0);
+        assert_eq!(
+            *log.borrow(),
+            [
+                "root Target Enter",
+                "middle Target Enter",
+                "root Capture Down(Primary)",
+                "middle Target Down(Primary)",
+                "middle Target Leave",
+                "root Target Move",
+            ]
+        );
+    }
+
+    #[test]
+    fn pointer_events_in_window() {
+        let clicks = Rc::new(RefCell::new(Vec::new()));
+        let button = |name: &'static str, width: f32, height: f32| {
+            let button = widget!( | Button |
+                width: Size::Pixel(width),
+                height: Size::Pixel(height),
+            );
+            let clicks = clicks.clone();
+            button
+                .on_click
+                .subscribe(move |_| clicks.borrow_mut().push(name))
+                .detach();
+            button
+        };
+
+        let headless = Window::headless("pointer");
+        let surface = headless.surface();
+        let press = |x, y| {
+            headless.inject(WindowEvent::Pointer(PointerEventKind::Move, x, y));
+            let primary = PointerButton::Primary;
+            headless.inject(WindowEvent::Pointer(PointerEventKind::Down(primary), x, y));
+            headless.inject(WindowEvent::Pointer(PointerEventKind::Up(primary), x, y));
+        };
+        // The small button is on top of the large one.
+        press(10.0, 10.0);
+        press(150.0, 80.0);
+        let small = StackItem {
+            child: Box::new(button("small", 50.0, 30.0)),
+            z_index: 1,
+            ..StackItem::default()
+        };
+        let large = button("large", 200.0, 100.0);
+
+        widget!(|crate::widget::Window|
+            width: Size::Pixel(200.0),
+            height: Size::Pixel(100.0),
+            child: Box::new(widget!(|StackLayout|
+                width: Size::Percent(100.0),
+                height: Size::Percent(100.0),
+                children: vec![Box::new(small) as Box<dyn Widget>, Box::new(large)],
+            )),
+        )
+        .render_on(headless)
+        .unwrap();
+
+        assert_eq!(*clicks.borrow(), ["small", "large"]);
+        // The large button stays hovered, which is painted darker.
+        let hovered = Color::from_rgba8(0xD0, 0xD0, 0xD0, 0xFF);
+        assert!(surface
+            .display_list()
+            .primitives()
+            .iter()
+            .any(|primitive| matches!(
+                primitive,
+                Primitive::RoundedRect { color, .. } if *color == hovered
+            )));
+    }
+
+    #[test]
+    fn keyboard_focus() {
+        let clicks = Rc::new(RefCell::new(Vec::new()));
+        let button = |name: &'static str, disabled: bool| {
+            let button = widget!( | Button |
+                width: Size::Pixel(50.0),
+                height: Size::Pixel(30.0),
+                text: name.to_string(),
+                disabled: disabled,
+            );
+            let clicks = clicks.clone();
+            button
+                .on_click
+                .subscribe(move |_| clicks.borrow_mut().push(name))
+                .detach();
+            button
+        };
+        let row = || {
+            let children: Widgets = vec![
+                Box::new(button("a", false)),
+                Box::new(button("b", true)),
+                Box::new(button("c", false)),
+            ];
+            widget!(|FlexLayout|
+                width: Size::Percent(100.0),
+                height: Size::Percent(100.0),
+                direction: FlexDirection::Row,
+                children: children,
+            )
+        };
+        assert_eq!(focus_order(&row()), [vec![0], vec![2]]);
+
+        let headless = Window::headless("keyboard");
+        let surface = headless.surface();
+        let key = |key, modifiers| {
+            headless.inject(WindowEvent::Keyboard(KeyEventKind::Down(key), modifiers));
+            headless.inject(WindowEvent::Keyboard(KeyEventKind::Up(key), modifiers));
+        };
+        // Tab skips the disabled button and wraps around.
+        key(Key::Tab, Modifiers::NONE);
+        key(Key::Tab, Modifiers::NONE);
+        // Held keys click once: Enter when pressed, Space when released.
+        let repeat = |key| {
+            headless.inject(WindowEvent::Keyboard(
+                KeyEventKind::Down(key),
+                Modifiers::NONE,
+            ))
+        };
+        repeat(Key::Enter);
+        key(Key::Enter, Modifiers::NONE);
+        key(Key::Tab, Modifiers::NONE);
+        key(Key::Tab, Modifiers::SHIFT);
+        repeat(Key::Space);
+        key(Key::Space, Modifiers::NONE);
+        // Clicking a button focuses it.
+        let primary = PointerButton::Primary;
+        headless.inject(WindowEvent::Pointer(
+            PointerEventKind::Down(primary),
+            10.0,
+            10.0,
+        ));
+        headless.inject(WindowEvent::Pointer(
+            PointerEventKind::Up(primary),
+            10.0,
+            10.0,
+        ));
+        headless.inject(WindowEvent::Pointer(PointerEventKind::Move, 200.0, 10.0));
+
+        widget!(|crate::widget::Window|
+            width: Size::Pixel(200.0),
+            height: Size::Pixel(100.0),
+            child: Box::new(row()),
+        )
+        .render_on(headless)
+        .unwrap();
+
+        assert_eq!(*clicks.borrow(), ["c", "c", "a"]);
+        let focused: Vec<_> = surface
+            .display_list()
+            .primitives()
+            .iter()
+            .filter_map(|primitive| match primitive {
+                Primitive::Border { rect, width, .. } if *width == 2.0 => Some(rect.x),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(focused, [0.0]);
+    }
+
+    #[test]
+    fn application_windows() {
+        let mut app = Application::headless();
+        let handle = app.handle();
+        let dialog = || {
+            widget!(|crate::widget::Window|
+                title: "Dialog",
+                width: Size::Pixel(100.0),
+                height: Size::Pixel(50.0),
+            )
+        };
+
+        let main = Rc::new(Cell::new(None));
+        let button = widget!( | Button |
+            width: Size::Pixel(100.0),
+            height: Size::Pixel(40.0),
+        );
+        let parent = main.clone();
+        button
+            .on_click
+            .subscribe(move |_| {
+                let parent = parent.get().unwrap();
+                handle.open_with(dialog(), WindowKind::Modal(parent));
+            })
+            .detach();
+        let id = app.open(widget!(|crate::widget::Window|
+            width: Size::Pixel(200.0),
+            height: Size::Pixel(100.0),
+            child: Box::new(widget!(|FlexLayout|
+                width: Size::Percent(100.0),
+                height: Size::Percent(100.0),
+                children: vec![Box::new(button) as Box<dyn Widget>],
+            )),
+        ));
+        main.set(Some(id));
+        let tool = app.open_with(dialog(), WindowKind::Tool(id));
+        assert_eq!(app.headless_window(tool).unwrap().title(), "Dialog");
+
+        let click = |app: &mut Application| {
+            let primary = PointerButton::Primary;
+            for kind in [
+                PointerEventKind::Down(primary),
+                PointerEventKind::Up(primary),
+            ] {
+                app.dispatch(id, WindowEvent::Pointer(kind, 10.0, 10.0));
+            }
+        };
+        click(&mut app);
+        let windows = app.windows();
+        assert_eq!(windows.len(), 3);
+        let modal = windows[2];
+        assert_eq!(app.kind(modal), Some(WindowKind::Modal(id)));
+        assert!(app.is_blocked(id));
+
+        // The modal window keeps the main window from opening another one.
+        click(&mut app);
+        assert_eq!(app.windows().len(), 3);
+
+        app.dispatch(modal, WindowEvent::WindowClosed);
+        assert_eq!(app.windows(), [id, tool]);
+        assert!(!app.is_blocked(id));
+
+        app.close(id);
+        assert!(app.windows().is_empty());
+        app.open_with(dialog(), WindowKind::Child(id));
+        assert!(app.windows().is_empty());
+    }
+
+    #[test]
+    fn close_requests_and_exit() {
+        let mut app = Application::headless();
+        let unsaved = Rc::new(Cell::new(true));
+        let window = widget!(|crate::widget::Window| title: "Editor");
+        let changes = unsaved.clone();
+        window
+            .on_close
+            .subscribe(move |request| {
+                if changes.get() {
+                    request.cancel();
+                }
+            })
+            .detach();
+        let id = app.open(window);
+
+        app.dispatch(id, WindowEvent::CloseRequested(CloseRequest::new()));
+        assert_eq!(app.windows(), [id]);
+        unsaved.set(false);
+        app.dispatch(id, WindowEvent::CloseRequested(CloseRequest::new()));
+        assert!(app.windows().is_empty());
+
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let app = Application::headless();
+        let started = log.clone();
+        app.on_start
+            .subscribe(move |handle| {
+                started.borrow_mut().push("start".to_string());
+                let id = handle.open(widget!(|crate::widget::Window| title: "Main"));
+                handle.close(id);
+                handle.exit(3);
+            })
+            .detach();
+        let exited = log.clone();
+        app.on_exit
+            .subscribe(move |code| exited.borrow_mut().push(format!("exit {}", code)))
+            .detach();
+        assert_eq!(app.run(), 3);
+        assert_eq!(*log.borrow(), ["start", "exit 3"]);
+    }
+
+    #[test]
+    fn posting_from_threads() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<AppProxy>();
+
+        let mut app = Application::headless();
+        let id = app.open(widget!(|crate::widget::Window| title: "Downloads"));
+        let received = Rc::new(RefCell::new(Vec::new()));
+        let messages = received.clone();
+        app.on_message
+            .subscribe(move |message| {
+                if let Some(progress) = message.get::<u32>() {
+                    messages.borrow_mut().push(*progress);
+                }
+                assert!(message.get::<String>().is_none());
+            })
+            .detach();
+
+        let proxy = app.proxy();
+        std::thread::spawn(move || {
+            for progress in [25u32, 50, 100] {
+                proxy.post(progress).unwrap();
+            }
+            proxy.request_redraw(id).unwrap();
+        })
+        .join()
+        .unwrap();
+        assert!(received.borrow().is_empty());
+
+        let proxy = app.proxy();
+        assert_eq!(app.run(), 0);
+        assert_eq!(*received.borrow(), [25, 50, 100]);
+        assert_eq!(proxy.post(()), Err(ApplicationClosed));
+    }
+
+    #[test]
+    fn widget_styles() {
+        let red = Color::rgb(1.0, 0.0, 0.0);
+        let label = widget!(|Label|
+            text: "Hi".to_string(),
+            style: Style {
+                color: Some(red),
+                background: Some(Color::WHITE),
+                border: Some(Border { width: 1.0, color: Color::BLACK }),
+                padding: Edges::all(4.0),
+                margin: Edges::symmetric(2.0, 6.0),
+                ..Style::default()
+            },
+        );
+        let (text_width, text_height) = label.content_size(Constraints::UNBOUNDED);
+        let (width, height) = label.measure(Constraints::UNBOUNDED);
+        assert_eq!((width, height), (text_width + 20.0, text_height + 12.0));
+
+        let bounds = Rect::new(0.0, 0.0, width, height);
+        let border_box = label.arrange(bounds).rect;
+        assert_eq!((border_box.x, border_box.y), (6.0, 2.0));
+        assert!((border_box.width - (text_width + 8.0)).abs() < 1e-3);
+        assert!((border_box.height - (text_height + 8.0)).abs() < 1e-3);
+        let mut list = DisplayList::new();
+        label.paint(&label.arrange(bounds), &mut list);
+        assert!(matches!(
+            list.primitives(),
+            [
+                Primitive::Rect { rect, color },
+                Primitive::Border { width, .. },
+                Primitive::Glyphs(run),
+            ] if *rect == border_box && *color == Color::WHITE && *width == 1.0
+                && (run.x, run.y, run.color) == (10.0, 6.0, red)
+        ));
+
+        let item = widget!(|Label| width: Size::Pixel(20.0), height: Size::Pixel(10.0));
+        let layout = widget!(|FlexLayout|
+            width: Size::Pixel(100.0),
+            height: Size::Pixel(50.0),
+            children: vec![Box::new(item) as Box<dyn Widget>],
+            style: Style {
+                padding: Edges::all(10.0),
+                opacity: 0.5,
+                ..Style::default()
+            },
+        );
+        let node = layout.arrange(Rect::new(0.0, 0.0, 100.0, 50.0));
+        assert_eq!(node.children[0].rect, Rect::new(10.0, 10.0, 20.0, 10.0));
+        let mut list = DisplayList::new();
+        layout.paint(&node, &mut list);
+        assert_eq!(
+            list.primitives().first(),
+            Some(&Primitive::PushLayer { opacity: 0.5 })
+        );
+        assert_eq!(list.primitives().last(), Some(&Primitive::PopLayer));
+
+        let shadow = Color::BLACK.with_alpha(0.25);
+        let button = widget!(|Button|
+            style: Style {
+                shadow: Some(Shadow { offset_x: 0.0, offset_y: 2.0, blur: 0.0, color: shadow }),
+                ..Button::default().style
+            },
+        );
+        let mut list = DisplayList::new();
+        button.paint(&button.arrange(Rect::new(0.0, 0.0, 60.0, 20.0)), &mut list);
+        assert_eq!(
+            list.primitives()[0],
+            Primitive::RoundedRect {
+                rect: Rect::new(0.0, 2.0, 60.0, 20.0),
+                radius: 4.0,
+                color: shadow,
+            }
+        );
+    }
+
+    #[test]
+    fn themes() {
+        use Size::*;
+
+        let light = Theme::light();
+        let dark = Theme::dark();
+        assert_eq!(Theme::for_scheme(ColorScheme::Dark), dark);
+        assert_eq!(*Theme::current(), light);
+
+        let mut app = Application::headless();
+        let id = app.open(widget!(|crate::widget::Window|
+            width: Pixel(100.0),
+            height: Pixel(40.0),
+            child: Box::new(widget!(|FlexLayout|
+                width: Percent(100.0),
+                height: Percent(100.0),
+                children: vec![
+                    Box::new(widget!(|Button| text: "OK".to_string())) as Box<dyn Widget>,
+                    Box::new(widget!(|Button|
+                        text: "Go".to_string(),
+                        style: Style { background: Some(light.palette.accent), ..Style::default() },
+                    )),
+                ],
+            )),
+        ));
+        let colors = |app: &Application| -> Vec<Color> {
+            let window = app.headless_window(id).unwrap();
+            window
+                .display_list()
+                .primitives()
+                .iter()
+                .filter_map(|primitive| match primitive {
+                    Primitive::Rect { color, .. } | Primitive::RoundedRect { color, .. } => {
+                        Some(*color)
+                    }
+                    _ => None,
+                })
+                .collect()
+        };
+        assert_eq!(
+            colors(&app),
+            [
+                light.palette.background,
+                light.palette.control,
+                light.palette.accent
+            ]
+        );
+
+        app.set_theme(dark.clone());
+        assert_eq!(
+            colors(&app),
+            [
+                dark.palette.background,
+                dark.palette.control,
+                light.palette.accent
+            ]
+        );
+        assert_eq!(Theme::current().color_scheme, ColorScheme::Dark);
+        Theme::set(Theme::light());
+    }
+
+    #[test]
+    fn errors() {
+        use crate::text::{Font, FontWeight};
+        use std::error::Error as _;
+
+        // The errors of the libraries doing the work are kept as the source.
+        let error = Font::from_bytes("broken", FontWeight::NORMAL, vec![1, 2, 3]).unwrap_err();
+        assert!(matches!(error, crate::Error::Font(_)));
+        assert!(error.to_string().starts_with("font could not be loaded"));
+        assert!(error.source().unwrap().is::<ab_glyph::InvalidFont>());
+
+        let error = Image::load("no/such/image.png").unwrap_err();
+        let source = error
+            .source()
+            .and_then(|source| source.downcast_ref::<std::io::Error>());
+        assert_eq!(
+            source.map(|io| io.kind()),
+            Some(std::io::ErrorKind::NotFound)
+        );
+        let error = Image::decode_png(&b"not a png"[..]).unwrap_err();
+        assert!(error.source().unwrap().is::<png::DecodingError>());
+
+        let error = crate::Error::AdapterNotFound;
+        assert_eq!(error.to_string(), "no suitable graphics adapter was found");
+        assert!(error.source().is_none());
+
+        let error = widget!(|crate::widget::Window|
+            title: "endless",
+            width: Size::Pixel(f32::INFINITY),
+            height: Size::Pixel(10.0),
+        )
+        .attach(&mut Window::headless("endless"))
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "layout failed: window `endless` resolves to inf by 10 pixels"
+        );
+        assert!(error.source().is_none());
+
+        // Opening right away reports what `open` only logs.
+        let mut app = Application::headless();
+        let endless = || {
+            widget!(|crate::widget::Window|
+                width: Size::Pixel(f32::INFINITY),
+            )
+        };
+        assert!(matches!(
+            app.try_open(endless()),
+            Err(crate::Error::Layout(_))
+        ));
+        app.open(endless());
+        assert!(app.windows().is_empty());
+        let id = app.try_open(widget!(|crate::widget::Window|)).unwrap();
+        assert_eq!(app.windows(), vec![id]);
+    }
+
+    #[test]
+    fn watching_style_files() {
+        use Size::*;
+
+        let dir = std::env::temp_dir().join(format!("upswing-watch-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let (sheet, theme) = (dir.join("app.css"), dir.join("theme.css"));
+        std::fs::write(&sheet, "Window { background: #ff0000; }").unwrap();
+        std::fs::write(&theme, "accent: #00ff00;").unwrap();
+
+        let mut app = Application::headless();
+        let id = app.open(widget!(|crate::widget::Window|
+            width: Pixel(100.0),
+            height: Pixel(40.0),
+        ));
+        let background = |app: &Application| {
+            let window = app.headless_window(id).unwrap();
+            window
+                .display_list()
+                .primitives()
+                .iter()
+                .find_map(|primitive| match primitive {
+                    Primitive::Rect { color, .. } => Some(*color),
+                    _ => None,
+                })
+        };
+        // Waits for the watcher threads to notice a change.
+        let settle = |app: &mut Application, done: &dyn Fn(&Application) -> bool| {
+            let start = std::time::Instant::now();
+            while !done(app) && start.elapsed() < std::time::Duration::from_secs(5) {
+                std::thread::sleep(Application::WATCH_INTERVAL / 2);
+                app.pump();
+            }
+        };
+
+        app.watch_stylesheet(id, &sheet).unwrap();
+        app.watch_theme(&theme).unwrap();
+        assert_eq!(background(&app), Some(Color::from_rgba8(0xFF, 0, 0, 0xFF)));
+        assert_eq!(
+            Theme::current().palette.accent,
+            Color::from_rgba8(0, 0xFF, 0, 0xFF)
+        );
+
+        std::fs::write(&sheet, "Window { background: #0000ff; padding: 2px; }").unwrap();
+        let blue = Some(Color::from_rgba8(0, 0, 0xFF, 0xFF));
+        settle(&mut app, &|app| background(app) == blue);
+        assert_eq!(background(&app), blue);
+
+        std::fs::write(&theme, "color-scheme: dark;").unwrap();
+        settle(&mut app, &|_| {
+            Theme::current().color_scheme == ColorScheme::Dark
+        });
+        assert_eq!(*Theme::current(), Theme::dark());
+
+        // A broken file is reported, and leaves things as they were.
+        std::fs::write(&sheet, "Window { background: ").unwrap();
+        std::thread::sleep(Application::WATCH_INTERVAL * 3);
+        app.pump();
+        assert_eq!(background(&app), blue);
+
+        drop(app);
+        Theme::set(Theme::light());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn stylesheets() {
+        let stylesheet = Stylesheet::parse(
+            "/* every button */
+            Button { background: #336699; border-radius: 6px }
+            .toolbar Button { padding: 2px 8px }
+            Button.primary { background: rgb(255, 0, 0); }
+            #save, Label { color: white; font-weight: bold }
+            Button:hover { opacity: 50% }
+            Window { background: #000 }",
+        )
+        .unwrap();
+        assert_eq!(stylesheet.rules().len(), 6);
+        assert_eq!(stylesheet.rules()[2].selectors[0].specificity(), (0, 1, 1));
+
+        let button = |id: Option<&str>, classes: &[&str]| -> Box<dyn Widget> {
+            Box::new(widget!(|Button|
+                text: "Button".to_string(),
+                id: id.map(str::to_string),
+                classes: classes.iter().map(|class| class.to_string()).collect(),
+            ))
+        };
+        let toolbar = widget!(|FlexLayout|
+            classes: vec!["toolbar".to_string()],
+            children: vec![button(Some("save"), &[]), button(None, &["primary"])],
+        );
+        let mut root = widget!(|FlexLayout|
+            children: vec![
+                Box::new(toolbar) as Box<dyn Widget>,
+                button(None, &[]),
+                Box::new(widget!(|Label| text: "Ready".to_string())),
+            ],
+        );
+        stylesheet.apply(&mut root);
+
+        let blue = Color::from_rgba8(0x33, 0x66, 0x99, 0xFF);
+        let save = root.children()[0].children()[0].style();
+        assert_eq!(save.background, Some(blue));
+        assert_eq!(save.corner_radius, Some(6.0));
+        assert_eq!(save.padding, Edges::symmetric(2.0, 8.0));
+        assert_eq!(save.color, Some(Color::WHITE));
+        assert_eq!(save.font_weight, Some(crate::text::FontWeight::BOLD));
+        let primary = root.children()[0].children()[1].style();
+        assert_eq!(primary.background, Some(Color::rgb(1.0, 0.0, 0.0)));
+        assert_eq!(root.children()[1].style().padding, Edges::ZERO);
+        assert_eq!(root.children()[2].style().color, Some(Color::WHITE));
+        assert_eq!(root.style(), &Style::default());
+
+        let mut hovered = widget!(|Button|);
+        hovered.pointer_enter();
+        stylesheet.apply(&mut hovered);
+        assert_eq!(hovered.style().opacity, 0.5);
+        hovered.pointer_leave();
+        stylesheet.apply(&mut hovered);
+        assert_eq!(hovered.style().opacity, 1.0);
+
+        let mut window = Window::headless("styled");
+        widget!(|crate::widget::Window|
+            width: Size::Pixel(100.0),
+            height: Size::Pixel(50.0),
+            stylesheet: Some(stylesheet),
+        )
+        .attach(&mut window)
+        .unwrap();
+        assert_eq!(
+            window.display_list().primitives()[1],
+            Primitive::Rect {
+                rect: Rect::new(0.0, 0.0, 100.0, 50.0),
+                color: Color::BLACK,
+            }
+        );
+
+        let error = |source| match Stylesheet::parse(source) {
+            Err(StylesheetError::Parse { line, message }) => (line, message),
+            other => panic!("expected a parse error, got {:?}", other),
+        };
+        assert_eq!(
+            error("Button {\n  colour: red;\n}"),
+            (2, "unknown property `colour`".to_string())
+        );
+        assert_eq!(
+            error("/* a\ncomment */ Label:hovered { }"),
+            (2, "unknown pseudo-class `:hovered`".to_string())
+        );
+        assert_eq!(
+            error("Label { color: #12 }"),
+            (1, "invalid color `#12`".to_string())
+        );
+        assert_eq!(error("Label { color: red").0, 1);
+        assert_eq!(
+            error("Button→ { }"),
+            (1, "unexpected `→` in selector `Button→`".to_string())
+        );
     }
 }
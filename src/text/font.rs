@@ -0,0 +1,166 @@
+use std::{
+    fmt, fs,
+    path::Path,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, OnceLock, RwLock,
+    },
+};
+
+use ab_glyph::{Font as _, FontVec, PxScale, ScaleFont};
+
+use crate::error::{Error, Result};
+
+/// The thickness of a font, on the CSS scale from 100 to 900.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FontWeight(pub u16);
+
+impl FontWeight {
+    pub const THIN: FontWeight = FontWeight(100);
+    pub const LIGHT: FontWeight = FontWeight(300);
+    pub const NORMAL: FontWeight = FontWeight(400);
+    pub const MEDIUM: FontWeight = FontWeight(500);
+    pub const BOLD: FontWeight = FontWeight(700);
+    pub const BLACK: FontWeight = FontWeight(900);
+}
+
+impl Default for FontWeight {
+    fn default() -> Self {
+        Self::NORMAL
+    }
+}
+
+/// Vertical metrics of a font at a given size, in pixels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LineMetrics {
+    /// Distance from the top of the line to the baseline.
+    pub ascent: f32,
+    /// Distance from the baseline to the bottom of the line, negative.
+    pub descent: f32,
+    pub line_gap: f32,
+}
+
+impl LineMetrics {
+    pub fn height(&self) -> f32 {
+        self.ascent - self.descent + self.line_gap
+    }
+}
+
+/// A TrueType or OpenType font. Cloning is cheap, clones share the data.
+#[derive(Clone)]
+pub struct Font {
+    inner: Arc<FontData>,
+}
+
+struct FontData {
+    id: u64,
+    family: String,
+    weight: FontWeight,
+    font: FontVec,
+}
+
+static NEXT_FONT_ID: AtomicU64 = AtomicU64::new(0);
+
+impl Font {
+    /// Parses a font file already in memory.
+    pub fn from_bytes(family: &str, weight: FontWeight, data: Vec<u8>) -> Result<Self> {
+        let font = FontVec::try_from_vec(data).map_err(|error| Error::Font(error.into()))?;
+        Ok(Self {
+            inner: Arc::new(FontData {
+                id: NEXT_FONT_ID.fetch_add(1, Ordering::Relaxed),
+                family: family.to_string(),
+                weight,
+                font,
+            }),
+        })
+    }
+
+    /// Reads and parses a font file.
+    pub fn load(family: &str, weight: FontWeight, path: impl AsRef<Path>) -> Result<Self> {
+        Self::from_bytes(
+            family,
+            weight,
+            fs::read(path).map_err(|error| Error::Font(error.into()))?,
+        )
+    }
+
+    /// Identifies the font in caches such as the
+    /// [`GlyphAtlas`](crate::renderer::GlyphAtlas).
+    pub fn id(&self) -> u64 {
+        self.inner.id
+    }
+
+    pub fn family(&self) -> &str {
+        &self.inner.family
+    }
+
+    pub fn weight(&self) -> FontWeight {
+        self.inner.weight
+    }
+
+    pub fn metrics(&self, size: f32) -> LineMetrics {
+        let font = self.scaled(size);
+        LineMetrics {
+            ascent: font.ascent(),
+            descent: font.descent(),
+            line_gap: font.line_gap(),
+        }
+    }
+
+    pub(crate) fn scaled(&self, size: f32) -> ab_glyph::PxScaleFont<&FontVec> {
+        self.inner.font.as_scaled(PxScale::from(size))
+    }
+}
+
+impl fmt::Debug for Font {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Font")
+            .field("id", &self.inner.id)
+            .field("family", &self.inner.family)
+            .field("weight", &self.inner.weight)
+            .finish()
+    }
+}
+
+impl PartialEq for Font {
+    fn eq(&self, other: &Self) -> bool {
+        self.inner.id == other.inner.id
+    }
+}
+
+/// The fonts widgets can refer to by family name.
+#[derive(Debug, Default)]
+pub struct FontBook {
+    fonts: Vec<Font>,
+}
+
+impl FontBook {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The book shared by every widget of the application.
+    pub fn global() -> &'static RwLock<FontBook> {
+        static BOOK: OnceLock<RwLock<FontBook>> = OnceLock::new();
+        BOOK.get_or_init(|| RwLock::new(FontBook::new()))
+    }
+
+    pub fn add(&mut self, font: Font) {
+        self.fonts.push(font);
+    }
+
+    /// The font of `family` closest to `weight`. Unknown families fall back to
+    /// every font in the book, so that text still shows up.
+    pub fn find(&self, family: &str, weight: FontWeight) -> Option<Font> {
+        let in_family = |font: &&Font| font.family().eq_ignore_ascii_case(family);
+        let candidates: Vec<&Font> = if self.fonts.iter().any(|font| in_family(&font)) {
+            self.fonts.iter().filter(in_family).collect()
+        } else {
+            self.fonts.iter().collect()
+        };
+        candidates
+            .into_iter()
+            .min_by_key(|font| font.weight().0.abs_diff(weight.0))
+            .cloned()
+    }
+}
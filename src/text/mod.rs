@@ -0,0 +1,4 @@
+pub mod font;
+pub mod shaping;
+pub use font::*;
+pub use shaping::*;
@@ -0,0 +1,172 @@
+use std::ops::Range;
+
+use ab_glyph::ScaleFont;
+
+use super::{Font, LineMetrics};
+use crate::renderer::bitmap_font;
+
+/// The font and size text is set in. Without a font, text falls back to the
+/// built-in [`bitmap_font`].
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TextStyle {
+    pub font: Option<Font>,
+    /// The size of the font in pixels.
+    pub size: f32,
+}
+
+impl TextStyle {
+    pub fn metrics(&self) -> LineMetrics {
+        match &self.font {
+            Some(font) => font.metrics(self.size),
+            None => {
+                let scale = bitmap_font::scale(self.size);
+                LineMetrics {
+                    ascent: bitmap_font::GLYPH_HEIGHT as f32 * scale,
+                    descent: 0.0,
+                    line_gap: (bitmap_font::LINE_HEIGHT - bitmap_font::GLYPH_HEIGHT) as f32 * scale,
+                }
+            }
+        }
+    }
+}
+
+/// One glyph of a run, positioned along the baseline.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ShapedGlyph {
+    /// The glyph in the font, or the character code for the bitmap font.
+    pub id: u16,
+    pub character: char,
+    /// Distance of the glyph origin from the start of the run.
+    pub x: f32,
+    pub advance: f32,
+}
+
+/// Maps `text` to glyphs, one per character, applying the font's pair
+/// kerning.
+///
+/// This is not full text shaping: ligatures, contextual forms and combining
+/// marks are not formed, each character is drawn with its own glyph, and
+/// text runs left to right. Scripts that depend on shaping, such as Arabic
+/// or Devanagari, do not render correctly.
+pub fn shape(text: &str, style: &TextStyle) -> Vec<ShapedGlyph> {
+    let mut pen = 0.0;
+    match &style.font {
+        Some(font) => {
+            let font = font.scaled(style.size);
+            let mut previous = None;
+            text.chars()
+                .map(|character| {
+                    let id = font.glyph_id(character);
+                    if let Some(previous) = previous {
+                        pen += font.kern(previous, id);
+                    }
+                    previous = Some(id);
+                    let glyph = ShapedGlyph {
+                        id: id.0,
+                        character,
+                        x: pen,
+                        advance: font.h_advance(id),
+                    };
+                    pen += glyph.advance;
+                    glyph
+                })
+                .collect()
+        }
+        None => {
+            let advance = bitmap_font::ADVANCE as f32 * bitmap_font::scale(style.size);
+            text.chars()
+                .map(|character| {
+                    let glyph = ShapedGlyph {
+                        id: character as u16,
+                        character,
+                        x: pen,
+                        advance,
+                    };
+                    pen += advance;
+                    glyph
+                })
+                .collect()
+        }
+    }
+}
+
+/// One line of laid out text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextLine {
+    pub text: String,
+    /// Top of the line box, from the top of the text.
+    pub y: f32,
+    /// Width without trailing whitespace.
+    pub width: f32,
+}
+
+/// Text broken into lines.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TextLayout {
+    pub lines: Vec<TextLine>,
+    pub width: f32,
+    pub height: f32,
+}
+
+/// Breaks `text` into lines at newlines and, given a `max_width`, wherever
+/// the next word would not fit. Words longer than a line are split.
+pub fn layout_text(text: &str, style: &TextStyle, max_width: Option<f32>) -> TextLayout {
+    let mut layout = TextLayout::default();
+    if text.is_empty() {
+        return layout;
+    }
+    let line_height = style.metrics().height();
+
+    for paragraph in text.split('\n') {
+        let glyphs = shape(paragraph, style);
+        let mut push = |range: Range<usize>| {
+            let line = line(&glyphs[range], layout.lines.len() as f32 * line_height);
+            layout.width = layout.width.max(line.width);
+            layout.lines.push(line);
+        };
+
+        let mut start = 0;
+        // The first glyph after the most recent run of whitespace.
+        let mut word_start = None;
+        for (index, glyph) in glyphs.iter().enumerate() {
+            if let Some(max_width) = max_width {
+                let right = glyph.x + glyph.advance - glyphs[start].x;
+                if index > start && !glyph.character.is_whitespace() && right > max_width {
+                    let end = match word_start {
+                        Some(word_start) if word_start > start => word_start,
+                        _ => index,
+                    };
+                    push(start..end);
+                    start = end;
+                    word_start = None;
+                }
+            }
+            if glyph.character.is_whitespace() {
+                word_start = Some(index + 1);
+            }
+        }
+        push(start..glyphs.len());
+    }
+
+    layout.height = layout.lines.len() as f32 * line_height;
+    layout
+}
+
+fn line(glyphs: &[ShapedGlyph], y: f32) -> TextLine {
+    let start = glyphs.first().map_or(0.0, |glyph| glyph.x);
+    let width = glyphs
+        .iter()
+        .rev()
+        .find(|glyph| !glyph.character.is_whitespace())
+        .map_or(0.0, |glyph| glyph.x + glyph.advance - start);
+    TextLine {
+        text: glyphs
+            .iter()
+            .map(|glyph| glyph.character)
+            .collect::<String>()
+            .trim_end()
+            .to_string(),
+        y,
+        width,
+    }
+}
@@ -1,2 +1,4 @@
+pub mod size;
 pub mod widget;
+pub use size::*;
 pub use widget::*;
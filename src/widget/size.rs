@@ -0,0 +1,119 @@
+use std::ops::{Add, Mul, Sub};
+
+use crate::layout::Viewport;
+
+/// The width or height of a widget, resolved to pixels by the layout pass.
+///
+/// Sizes combine like CSS `calc()` expressions:
+/// `Size::Percent(50.0) - Size::Pixel(8.0)` is half of the parent minus 8px.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Size {
+    /// Physical pixels.
+    Pixel(f32),
+    /// DPI-independent pixels, multiplied by the viewport's scale factor.
+    Logical(f32),
+    /// A percentage of the space offered by the parent.
+    Percent(f32),
+    /// A percentage of the viewport's width.
+    ViewportWidth(f32),
+    /// A percentage of the viewport's height.
+    ViewportHeight(f32),
+    /// As large as the widget's content.
+    Auto,
+    /// A share of the space left by the siblings. Flex layouts split it by
+    /// the given fractions, elsewhere it takes all of the offered space.
+    Fill(f32),
+    /// The smaller of the two sizes.
+    Min(Box<Size>, Box<Size>),
+    /// The larger of the two sizes.
+    Max(Box<Size>, Box<Size>),
+    Sum(Box<Size>, Box<Size>),
+    Difference(Box<Size>, Box<Size>),
+    Scaled(Box<Size>, f32),
+}
+
+impl Default for Size {
+    fn default() -> Self {
+        Self::Pixel(0.0)
+    }
+}
+
+impl Size {
+    /// The size in pixels, with percentages taken of `available`.
+    pub fn resolve(&self, available: f32) -> f32 {
+        self.resolve_with(available, 0.0)
+    }
+
+    /// The size in pixels, with percentages taken of `available` and `content`
+    /// standing in for [`Size::Auto`].
+    pub fn resolve_with(&self, available: f32, content: f32) -> f32 {
+        let viewport = Viewport::current();
+        let resolve = |size: &Size| size.resolve_with(available, content);
+        match self {
+            Size::Pixel(number) => *number,
+            Size::Logical(number) => number * viewport.scale_factor,
+            Size::Percent(number) => available * number / 100.0,
+            Size::ViewportWidth(number) => viewport.width * number / 100.0,
+            Size::ViewportHeight(number) => viewport.height * number / 100.0,
+            Size::Auto => content,
+            Size::Fill(_) => available,
+            Size::Min(a, b) => resolve(a).min(resolve(b)),
+            Size::Max(a, b) => resolve(a).max(resolve(b)),
+            Size::Sum(a, b) => resolve(a) + resolve(b),
+            Size::Difference(a, b) => resolve(a) - resolve(b),
+            Size::Scaled(size, factor) => resolve(size) * factor,
+        }
+    }
+
+    /// Keeps this size between `min` and `max`.
+    pub fn clamp(self, min: Size, max: Size) -> Size {
+        Size::Max(
+            Box::new(min),
+            Box::new(Size::Min(Box::new(self), Box::new(max))),
+        )
+    }
+
+    /// The fraction of a [`Size::Fill`], `None` for every other size.
+    pub fn fill(&self) -> Option<f32> {
+        match self {
+            Size::Fill(fraction) => Some(*fraction),
+            _ => None,
+        }
+    }
+
+    /// Whether resolving this size needs the widget's content to be measured.
+    pub fn depends_on_content(&self) -> bool {
+        match self {
+            Size::Auto => true,
+            Size::Min(a, b) | Size::Max(a, b) | Size::Sum(a, b) | Size::Difference(a, b) => {
+                a.depends_on_content() || b.depends_on_content()
+            }
+            Size::Scaled(size, _) => size.depends_on_content(),
+            _ => false,
+        }
+    }
+}
+
+impl Add for Size {
+    type Output = Size;
+
+    fn add(self, other: Size) -> Size {
+        Size::Sum(Box::new(self), Box::new(other))
+    }
+}
+
+impl Sub for Size {
+    type Output = Size;
+
+    fn sub(self, other: Size) -> Size {
+        Size::Difference(Box::new(self), Box::new(other))
+    }
+}
+
+impl Mul<f32> for Size {
+    type Output = Size;
+
+    fn mul(self, factor: f32) -> Size {
+        Size::Scaled(Box::new(self), factor)
+    }
+}
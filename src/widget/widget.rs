@@ -1,7 +1,3 @@
-use std::fmt::Debug;
-
-use winit::event::WindowEvent;
-
 pub mod macros {
     use crate::widget::Widget;
     pub fn is_widget<T: Widget>(widget: T) -> T {
@@ -12,6 +8,7 @@ pub mod macros {
     macro_rules! widget {
         (|$type:ty| $($field:ident : $value:expr),* $(,)?) => {
             {
+                #[allow(unused_mut)]
                 let mut instance: $type = Default::default();
                 $(instance.$field = $value;)*
                 instance
@@ -30,7 +27,9 @@ pub mod macros {
         };
     }
 
+    #[allow(unused_imports)]
     pub(crate) use widget;
+    #[allow(unused_imports)]
     pub(crate) use widgets;
 }
 
@@ -39,26 +38,46 @@ macro_rules! common_widget {
         fn _get_mut_size(&mut self) -> (&mut Size, &mut Size) {
             (&mut self.width, &mut self.height)
         }
+        fn _get_size(&self) -> (Size, Size) {
+            (self.width.clone(), self.height.clone())
+        }
+        fn style(&self) -> &Style {
+            self.computed_style.as_ref().unwrap_or(&self.style)
+        }
+        fn set_computed_style(&mut self, style: Option<Style>) {
+            self.computed_style = style;
+        }
+        fn id(&self) -> Option<&str> {
+            self.id.as_deref()
+        }
+        fn classes(&self) -> &[String] {
+            &self.classes
+        }
     }
 }
 
-// use crate::renderer::Renderer;
+use crate::event::window::{CloseRequest, WindowEvent};
+use crate::event::{
+    Event, FocusEvent, FocusManager, Key, KeyEvent, KeyEventKind, PointerButton, PointerEvent,
+    PointerEventKind, PointerRouter,
+};
+use crate::layout::{
+    flex, grid, paint_order, stack, with_viewport, AlignItems, Anchor, Constraints, FlexChild,
+    FlexContainer, FlexDirection, FlexWrap, GridChild, GridContainer, GridPlacement,
+    JustifyContent, LayoutNode, StackChild, StackContainer, StackPlacement, Track, Viewport,
+};
+use crate::renderer::DisplayList;
+use crate::style::{Border, Element, PseudoClasses, Style, Stylesheet, Theme};
+use crate::text::{layout_text, FontBook, FontWeight, TextStyle};
+use crate::utils::Rect;
 
-#[derive(Debug, Clone, Copy)]
-pub enum Size {
-    Pixel(f32),
-    Percent(f32),
-}
-pub type Widgets = Vec<Box<dyn Widget>>;
+use crate::widget::Size;
 
-impl Default for Size {
-    fn default() -> Self {
-        Self::Pixel(0.0)
-    }
-}
+pub type Widgets = Vec<Box<dyn Widget>>;
 
 pub trait Widget: std::fmt::Debug {
     fn _get_mut_size(&mut self) -> (&mut Size, &mut Size);
+    fn _get_size(&self) -> (Size, Size);
     fn get_size(&mut self) -> (Size, Size) {
         let (w, h) = self._get_mut_size();
         (w.clone(), h.clone())
@@ -69,6 +88,123 @@ pub trait Widget: std::fmt::Debug {
         *h = height;
     }
     fn render(self);
+    /// How this widget looks, including the margin and padding that layout
+    /// leaves around it.
+    fn style(&self) -> &Style {
+        &Style::DEFAULT
+    }
+    /// Replaces the style this widget is painted with by one computed from a
+    /// [`Stylesheet`], or goes back to its own with `None`.
+    fn set_computed_style(&mut self, _style: Option<Style>) {}
+    /// The name type selectors match, that of the widget's type.
+    fn type_name(&self) -> &'static str {
+        let name = std::any::type_name::<Self>();
+        name.rsplit("::").next().unwrap_or(name)
+    }
+    /// The name `#id` selectors match.
+    fn id(&self) -> Option<&str> {
+        None
+    }
+    /// The names `.class` selectors match.
+    fn classes(&self) -> &[String] {
+        &[]
+    }
+    /// The states pseudo-classes such as `:hover` match.
+    fn pseudo_classes(&self) -> PseudoClasses {
+        PseudoClasses::default()
+    }
+    /// The measure pass: the size this widget wants within `constraints`,
+    /// with percentages taken of the space offered by the parent. The size
+    /// includes the margin of the widget's style.
+    fn measure(&self, constraints: Constraints) -> (f32, f32) {
+        let style = self.style();
+        let (width, height) = self._get_size();
+        let (available_width, available_height) = constraints.available();
+        let (content_width, content_height) =
+            if width.depends_on_content() || height.depends_on_content() {
+                let (content_width, content_height) =
+                    self.content_size(style.content_constraints(constraints));
+                (
+                    content_width + style.padding.horizontal(),
+                    content_height + style.padding.vertical(),
+                )
+            } else {
+                (0.0, 0.0)
+            };
+        constraints.constrain(
+            width.resolve_with(available_width, content_width) + style.margin.horizontal(),
+            height.resolve_with(available_height, content_height) + style.margin.vertical(),
+        )
+    }
+    /// The natural size of what this widget shows, without padding, used for
+    /// [`Size::Auto`].
+    fn content_size(&self, _constraints: Constraints) -> (f32, f32) {
+        (0.0, 0.0)
+    }
+    /// The arrange pass: places this widget, and recursively its children,
+    /// at the final `bounds` chosen by the parent.
+    fn arrange(&self, bounds: Rect) -> LayoutNode {
+        LayoutNode::leaf(self.style().border_rect(bounds))
+    }
+    /// The paint pass: emits the primitives drawing this widget where
+    /// [`arrange`](Self::arrange) placed it, and its children at their nodes
+    /// of `layout`.
+    fn paint(&self, _layout: &LayoutNode, _list: &mut DisplayList) {}
+    /// How this widget behaves as the child of a [`FlexLayout`].
+    fn flex(&self) -> Flex {
+        Flex::default()
+    }
+    /// Where this widget sits as the child of a [`GridLayout`].
+    fn grid(&self) -> GridPlacement {
+        GridPlacement::default()
+    }
+    /// How this widget is positioned as the child of a [`StackLayout`].
+    fn stack(&self) -> StackPlacement {
+        StackPlacement::default()
+    }
+    /// The children of a container, in the order they were arranged in.
+    fn children(&self) -> &[Box<dyn Widget>] {
+        &[]
+    }
+    fn children_mut(&mut self) -> &mut [Box<dyn Widget>] {
+        &mut []
+    }
+    /// The indices of the children from the bottom-most to the top-most.
+    fn paint_order(&self) -> Vec<usize> {
+        (0..self.children().len()).collect()
+    }
+    /// Handles a pointer event routed to this widget, laid out at `bounds`.
+    fn on_pointer(&mut self, _event: &mut PointerEvent, _bounds: Rect) {}
+    /// Whether this widget takes part in Tab navigation and can receive the
+    /// keyboard focus.
+    fn focusable(&self) -> bool {
+        false
+    }
+    /// Handles a keyboard event sent to the focused widget or its ancestors.
+    fn on_key(&mut self, _event: &mut KeyEvent) {}
+    /// Called when this widget gains or loses the keyboard focus.
+    fn on_focus(&mut self, _event: FocusEvent) {}
+}
+
+/// The per-child flex properties, set by wrapping a child in a [`FlexItem`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Flex {
+    pub grow: f32,
+    pub shrink: f32,
+    /// The main size before growing or shrinking, the child's own size if unset.
+    pub basis: Option<Size>,
+    pub align_self: Option<AlignItems>,
+}
+
+impl Default for Flex {
+    fn default() -> Self {
+        Self {
+            grow: 0.0,
+            shrink: 1.0,
+            basis: None,
+            align_self: None,
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -92,6 +228,35 @@ pub struct Window {
     pub height: Size,
     pub title: &'static str,
     pub child: Box<dyn Container>,
+    /// Whether the user can resize the window.
+    pub resizable: bool,
+    /// Where the top-left corner goes on the screen, left to the platform
+    /// when `None`.
+    pub position: Option<(f32, f32)>,
+    pub fullscreen: bool,
+    /// The smallest size the user can resize the window to, resolved against
+    /// the screen like the window's own size.
+    pub min_size: Option<(Size, Size)>,
+    /// The largest size the user can resize the window to.
+    pub max_size: Option<(Size, Size)>,
+    /// Whether the window has a title bar and borders.
+    pub decorations: bool,
+    pub style: Style,
+    /// Selected by `#id` in a [`Stylesheet`].
+    pub id: Option<String>,
+    /// Selected by `.class` in a [`Stylesheet`].
+    pub classes: Vec<String>,
+    computed_style: Option<Style>,
+    /// Styles the window and its widget tree, which is restyled whenever an
+    /// event changes it.
+    pub stylesheet: Option<Stylesheet>,
+    /// Triggered when the user asks to close the window, which a handler can
+    /// cancel.
+    pub on_close: Event<CloseRequest>,
+    pointer: PointerRouter,
+    focus: FocusManager,
+    /// The area the window was last laid out in.
+    viewport: Viewport,
 }
 
 impl Default for Window {
@@ -103,53 +268,585 @@ impl Default for Window {
             child: Box::new(FlexLayout {
                 ..Default::default()
             }),
+            resizable: true,
+            position: None,
+            fullscreen: false,
+            min_size: None,
+            max_size: None,
+            decorations: true,
+            style: Style::default(),
+            id: None,
+            classes: Vec::new(),
+            computed_style: None,
+            stylesheet: None,
+            on_close: Event::new(),
+            pointer: PointerRouter::new(),
+            focus: FocusManager::new(),
+            viewport: Viewport::default(),
         }
     }
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone)]
 pub struct Label {
     pub width: Size,
     pub height: Size,
+    pub text: String,
+    /// A family added to the [`FontBook`], the bitmap font if the book is empty.
+    pub font_family: String,
+    /// The size of the font in pixels.
+    pub font_size: f32,
+    pub font_weight: FontWeight,
+    pub style: Style,
+    /// Selected by `#id` in a [`Stylesheet`].
+    pub id: Option<String>,
+    /// Selected by `.class` in a [`Stylesheet`].
+    pub classes: Vec<String>,
+    computed_style: Option<Style>,
 }
 
-#[derive(Debug, Clone, Default)]
+impl Default for Label {
+    /// A label in the body font of the current theme.
+    fn default() -> Self {
+        let theme = Theme::current();
+        Self {
+            width: Size::Auto,
+            height: Size::Auto,
+            text: String::new(),
+            font_family: theme.typography.font_family.clone(),
+            font_size: theme.typography.body,
+            font_weight: FontWeight::NORMAL,
+            style: Style::default(),
+            id: None,
+            classes: Vec::new(),
+            computed_style: None,
+        }
+    }
+}
+
+impl Label {
+    /// The font of the label, as overridden by its style.
+    pub fn text_style(&self) -> TextStyle {
+        let book = FontBook::global().read().unwrap_or_else(|e| e.into_inner());
+        let family = self
+            .style()
+            .font_family
+            .as_ref()
+            .unwrap_or(&self.font_family);
+        TextStyle {
+            font: book.find(family, self.style().font_weight.unwrap_or(self.font_weight)),
+            size: self.style().font_size.unwrap_or(self.font_size),
+        }
+    }
+}
+
+#[derive(Debug)]
 pub struct Button {
     pub width: Size,
     pub height: Size,
+    pub text: String,
+    /// A disabled button ignores input and is drawn greyed out.
+    pub disabled: bool,
+    pub style: Style,
+    /// Selected by `#id` in a [`Stylesheet`].
+    pub id: Option<String>,
+    /// Selected by `.class` in a [`Stylesheet`].
+    pub classes: Vec<String>,
+    computed_style: Option<Style>,
+    /// Triggered when the button is released under the pointer after being
+    /// pressed, or clicked programmatically.
+    pub on_click: Event<()>,
+    state: ButtonState,
+    /// Enter or Space while held down on the focused button, so that the
+    /// repeats of a held key are told apart from new presses.
+    held_key: Option<Key>,
+}
+
+/// How a [`Button`] is currently interacted with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ButtonState {
+    pub hovered: bool,
+    pub pressed: bool,
+    pub focused: bool,
+}
+
+impl Default for Button {
+    fn default() -> Self {
+        Self {
+            width: Size::Auto,
+            height: Size::Auto,
+            text: String::new(),
+            disabled: false,
+            style: Style::default(),
+            id: None,
+            classes: Vec::new(),
+            computed_style: None,
+            on_click: Event::new(),
+            state: ButtonState::default(),
+            held_key: None,
+        }
+    }
+}
+
+impl Button {
+    /// Space between the border and the text.
+    pub const PADDING_X: f32 = 12.0;
+    pub const PADDING_Y: f32 = 6.0;
+
+    pub fn state(&self) -> ButtonState {
+        self.state
+    }
+
+    pub fn pointer_enter(&mut self) {
+        self.state.hovered = true;
+    }
+
+    /// Leaving the button also cancels a press, so no click follows.
+    pub fn pointer_leave(&mut self) {
+        self.state.hovered = false;
+        self.state.pressed = false;
+    }
+
+    pub fn pointer_down(&mut self) {
+        if !self.disabled {
+            self.state.pressed = true;
+        }
+    }
+
+    /// Ends a press, clicking when the pointer is still over the button.
+    pub fn pointer_up(&mut self) {
+        let clicked = self.state.pressed && self.state.hovered;
+        self.state.pressed = false;
+        if clicked {
+            self.click();
+        }
+    }
+
+    pub fn set_focused(&mut self, focused: bool) {
+        self.state.focused = focused;
+    }
+
+    /// Triggers `on_click`, unless the button is disabled.
+    pub fn click(&self) {
+        if !self.disabled {
+            self.on_click.trigger(());
+        }
+    }
+
+    fn label(&self) -> Label {
+        Label {
+            text: self.text.clone(),
+            style: Style {
+                font_family: self.style().font_family.clone(),
+                font_size: self.style().font_size,
+                font_weight: self.style().font_weight,
+                ..Style::default()
+            },
+            ..Label::default()
+        }
+    }
 }
 
 impl Widget for Label {
     common_widget!();
     fn render(self) {}
+    fn content_size(&self, constraints: Constraints) -> (f32, f32) {
+        let max_width = Some(constraints.max_width).filter(|width| width.is_finite());
+        let layout = layout_text(&self.text, &self.text_style(), max_width);
+        (layout.width, layout.height)
+    }
+    fn paint(&self, layout: &LayoutNode, list: &mut DisplayList) {
+        let style = self.text_style();
+        let color = self.style().color.unwrap_or(Theme::current().palette.text);
+        self.style().paint(layout.rect, list, |bounds, list| {
+            for line in layout_text(&self.text, &style, Some(bounds.width)).lines {
+                list.styled_text(&line.text, bounds.x, bounds.y + line.y, &style, color);
+            }
+        });
+    }
 }
 impl Widget for Button {
     common_widget!();
     fn render(self) {}
-}
-impl Widget for Window {
-    common_widget!();
-    fn render(self) {
-        use crate::event::window::WindowEvent;
-        use crate::platform::{Window, WindowInterface};
-        let mut window = Window::start(self.title);
-        let width = match self.width {
-            Size::Pixel(number) => number,
-            Size::Percent(number) => number,
+    fn content_size(&self, constraints: Constraints) -> (f32, f32) {
+        if self.text.is_empty() {
+            return (0.0, 0.0);
+        }
+        let padding = (2.0 * Button::PADDING_X, 2.0 * Button::PADDING_Y);
+        let constraints = Constraints {
+            max_width: constraints.max_width - padding.0,
+            ..constraints
+        };
+        let (width, height) = self.label().content_size(constraints);
+        (width + padding.0, height + padding.1)
+    }
+    fn paint(&self, layout: &LayoutNode, list: &mut DisplayList) {
+        let theme = Theme::current();
+        let palette = &theme.palette;
+        let state = self.state;
+        let (background, foreground) = if self.disabled {
+            (palette.control_disabled, palette.text_disabled)
+        } else if state.pressed && state.hovered {
+            (palette.control_pressed, palette.text)
+        } else if state.hovered {
+            (palette.control_hovered, palette.text)
+        } else {
+            (palette.control, palette.text)
         };
-        let height = match self.width {
-            Size::Pixel(number) => number,
-            Size::Percent(number) => number,
+        // The colors of the style replace the defaults of an enabled button.
+        let style = if self.disabled {
+            Style {
+                color: None,
+                background: None,
+                ..self.style().clone()
+            }
+        } else {
+            self.style().clone()
         };
+        let style = style.or(&Style {
+            color: Some(foreground),
+            background: Some(background),
+            border: Some(Border {
+                width: 1.0,
+                color: palette.border,
+            }),
+            corner_radius: Some(theme.radii.small),
+            ..Style::default()
+        });
+        style.with_opacity(list, |list| {
+            let rect = layout.rect;
+            style.paint_shadow(rect, list);
+            style.paint_background(rect, style.background.unwrap_or(background), list);
+            if state.focused && !self.disabled {
+                let radius = style.corner_radius.unwrap_or_default();
+                list.border(rect, 2.0, radius, palette.accent);
+            } else {
+                style.paint_border(rect, list);
+            }
 
-        window.set_size(width, height);
+            let content = style.padding.inset(rect);
+            let label = self.label();
+            let text_style = label.text_style();
+            let layout = layout_text(
+                &label.text,
+                &text_style,
+                Some(content.width - 2.0 * Button::PADDING_X),
+            );
+            let top = content.y + (content.height - layout.height) / 2.0;
+            let color = style.color.unwrap_or(foreground);
+            for line in layout.lines {
+                let left = content.x + (content.width - line.width) / 2.0;
+                list.styled_text(&line.text, left, top + line.y, &text_style, color);
+            }
+        });
+    }
+    fn on_pointer(&mut self, event: &mut PointerEvent, _bounds: Rect) {
+        match event.kind {
+            PointerEventKind::Enter => self.pointer_enter(),
+            PointerEventKind::Leave => self.pointer_leave(),
+            PointerEventKind::Down(PointerButton::Primary) => {
+                self.pointer_down();
+                event.stop_propagation();
+            }
+            PointerEventKind::Up(PointerButton::Primary) => self.pointer_up(),
+            _ => {}
+        }
+    }
+    fn focusable(&self) -> bool {
+        !self.disabled
+    }
+    fn pseudo_classes(&self) -> PseudoClasses {
+        PseudoClasses {
+            hover: self.state.hovered,
+            pressed: self.state.pressed && self.state.hovered,
+            focus: self.state.focused,
+            disabled: self.disabled,
+        }
+    }
+    /// Enter clicks when pressed and Space when released, as in native
+    /// toolkits. Holding either key clicks only once.
+    fn on_key(&mut self, event: &mut KeyEvent) {
+        match event.kind {
+            KeyEventKind::Down(key @ (Key::Enter | Key::Space)) => {
+                if self.held_key != Some(key) {
+                    self.held_key = Some(key);
+                    if key == Key::Enter {
+                        self.click();
+                    }
+                }
+                event.stop_propagation();
+            }
+            KeyEventKind::Up(key @ (Key::Enter | Key::Space)) if self.held_key == Some(key) => {
+                self.held_key = None;
+                if key == Key::Space {
+                    self.click();
+                }
+                event.stop_propagation();
+            }
+            _ => {}
+        }
+    }
+    fn on_focus(&mut self, event: FocusEvent) {
+        self.set_focused(event == FocusEvent::Focus);
+        if event != FocusEvent::Focus {
+            self.held_key = None;
+        }
+    }
+}
+impl Window {
+    /// The size of this window on `screen`, with percentages and viewport
+    /// units taken of the screen, kept within the minimum and maximum sizes.
+    pub fn resolve_size(&self, screen: Viewport) -> (f32, f32) {
+        with_viewport(screen, || {
+            let (width, height) = (&self.width, &self.height);
+            let (content_width, content_height) =
+                if width.depends_on_content() || height.depends_on_content() {
+                    let screen = Constraints::loose(screen.width, screen.height);
+                    let (content_width, content_height) =
+                        self.content_size(self.style().content_constraints(screen));
+                    (
+                        content_width + self.style().padding.horizontal(),
+                        content_height + self.style().padding.vertical(),
+                    )
+                } else {
+                    (0.0, 0.0)
+                };
+            let (min_width, min_height) = self.resolve_limit(&self.min_size, screen, 0.0);
+            let (max_width, max_height) = self.resolve_limit(&self.max_size, screen, f32::INFINITY);
+            (
+                width
+                    .resolve_with(screen.width, content_width)
+                    .min(max_width)
+                    .max(min_width),
+                height
+                    .resolve_with(screen.height, content_height)
+                    .min(max_height)
+                    .max(min_height),
+            )
+        })
+    }
+
+    fn resolve_limit(
+        &self,
+        limit: &Option<(Size, Size)>,
+        screen: Viewport,
+        none: f32,
+    ) -> (f32, f32) {
+        with_viewport(screen, || match limit {
+            Some((width, height)) => (width.resolve(screen.width), height.resolve(screen.height)),
+            None => (none, none),
+        })
+    }
+
+    /// Paints the whole widget tree of this window at its own size.
+    pub fn display_list(&self) -> DisplayList {
+        let (width, height) = self.resolve_size(Viewport::default());
+        self.display_list_in(Viewport {
+            width,
+            height,
+            ..Viewport::default()
+        })
+    }
 
-        window.handle_events(|event| match event {
-            WindowEvent::WindowClosed => std::process::exit(0),
-            _ => (),
+    /// Paints the whole widget tree filling `viewport`, the window's area.
+    pub fn display_list_in(&self, viewport: Viewport) -> DisplayList {
+        let mut list = DisplayList::new();
+        with_viewport(viewport, || {
+            let bounds = Rect::new(0.0, 0.0, viewport.width, viewport.height);
+            self.paint(&self.arrange(bounds), &mut list);
         });
+        list
+    }
 
+    /// Opens this window in a new [`Application`](crate::platform::Application)
+    /// and runs it until the window is closed, returning the exit code.
+    pub fn try_render(self) -> crate::Result<i32> {
+        let mut application = crate::platform::Application::new();
+        application.try_open(self)?;
+        Ok(application.run())
+    }
+
+    /// Drives this window's widget tree on an already created platform window,
+    /// such as a [`HeadlessWindow`](crate::platform::HeadlessWindow), until it
+    /// is closed. Fails like [`attach`](Self::attach), without showing it.
+    pub fn render_on<W: crate::platform::WindowInterface>(
+        self,
+        mut window: W,
+    ) -> crate::Result<()> {
+        self.attach(&mut window)?;
         window.show();
+        Ok(())
+    }
+
+    /// Configures `window` for this widget tree, paints it and keeps it up to
+    /// date with the window's events, without showing it.
+    ///
+    /// Fails with [`Error::Layout`](crate::Error::Layout) if the window's size
+    /// does not resolve to a finite number of pixels on the window's screen.
+    pub fn attach<W: crate::platform::WindowInterface>(
+        mut self,
+        window: &mut W,
+    ) -> crate::Result<()> {
+        self.restyle();
+        let screen = window.screen();
+        let (width, height) = self.resolve_size(screen);
+        if !(width.is_finite() && height.is_finite()) {
+            return Err(crate::Error::Layout(format!(
+                "window `{}` resolves to {} by {} pixels",
+                self.title, width, height
+            )));
+        }
+        self.configure(window);
+        window.set_size(width, height);
+        self.viewport = Viewport {
+            width,
+            height,
+            scale_factor: screen.scale_factor,
+        };
+        let surface = window.surface();
+        surface.draw(self.display_list_in(self.viewport));
+
+        let this = std::cell::RefCell::new(self);
+        window.handle_events(move |event| {
+            let mut this = this.borrow_mut();
+            if this.handle_event(&event) {
+                surface.draw(this.display_list_in(this.viewport));
+            }
+        });
+        Ok(())
+    }
+
+    /// Applies the window's attributes, other than its size, to a platform
+    /// window. Called by [`render_on`](Self::render_on).
+    pub fn configure<W: crate::platform::WindowInterface>(&self, window: &mut W) {
+        let screen = window.screen();
+        let resolve = |limit: &Option<(Size, Size)>| {
+            limit
+                .as_ref()
+                .map(|_| self.resolve_limit(limit, screen, 0.0))
+        };
+        window.set_title(self.title);
+        window.set_resizeable(self.resizable);
+        window.set_decorations(self.decorations);
+        window.set_min_size(resolve(&self.min_size));
+        window.set_max_size(resolve(&self.max_size));
+        if let Some((x, y)) = self.position {
+            window.set_pos(x, y);
+        }
+        window.set_fullscreen(self.fullscreen);
+    }
+
+    /// Applies the window's stylesheet to it and its widget tree, for the
+    /// current state of each widget.
+    pub fn restyle(&mut self) {
+        if let Some(stylesheet) = self.stylesheet.take() {
+            stylesheet.apply(self);
+            let window = Element::of(self);
+            stylesheet.apply_within(&mut *self.child, &[window]);
+            self.stylesheet = Some(stylesheet);
+        }
+    }
+
+    /// Updates the widget tree for one event, returning whether it needs to
+    /// be painted again.
+    pub fn handle_event(&mut self, event: &WindowEvent) -> bool {
+        let repaint = self.update(event);
+        if repaint {
+            self.restyle();
+        }
+        repaint
+    }
+
+    fn update(&mut self, event: &WindowEvent) -> bool {
+        match *event {
+            WindowEvent::WindowResize(width, height) => {
+                self.viewport.width = width;
+                self.viewport.height = height;
+                true
+            }
+            WindowEvent::ScaleFactorChanged(scale_factor) => {
+                self.viewport.scale_factor = scale_factor;
+                true
+            }
+            WindowEvent::RedrawRequested => true,
+            WindowEvent::StylesheetChanged(ref stylesheet) => {
+                self.stylesheet = Some(stylesheet.clone());
+                true
+            }
+            WindowEvent::CloseRequested(ref request) => {
+                self.on_close.trigger(request.clone());
+                false
+            }
+            WindowEvent::Pointer(kind, x, y) => {
+                let viewport = self.viewport;
+                let bounds = Rect::new(0.0, 0.0, viewport.width, viewport.height);
+                let layout =
+                    with_viewport(viewport, || self.child.arrange(self.child_rect(bounds)));
+                let child: &mut dyn Widget = &mut *self.child;
+                with_viewport(viewport, || {
+                    self.pointer.handle(child, &layout, kind, x, y);
+                });
+                if let PointerEventKind::Down(_) = kind {
+                    let path = self.pointer.hovered().unwrap_or_default().to_vec();
+                    self.focus.focus_within(child, path);
+                }
+                true
+            }
+            WindowEvent::Keyboard(kind, modifiers) => {
+                with_viewport(self.viewport, || {
+                    self.focus.handle(&mut *self.child, kind, modifiers);
+                });
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Window {
+    /// Where the child goes within the window's `bounds`, inside its padding.
+    fn child_rect(&self, bounds: Rect) -> Rect {
+        let bounds = self.style().content_rect(bounds);
+        let (width, height) = self
+            .child
+            .measure(Constraints::loose(bounds.width, bounds.height));
+        Rect::new(bounds.x, bounds.y, width, height)
+    }
+}
+
+impl Widget for Window {
+    common_widget!();
+    /// Runs [`try_render`](Window::try_render), logging why the window could
+    /// not be opened, or the exit code if it is not 0.
+    fn render(self) {
+        match self.try_render() {
+            Ok(0) => {}
+            Ok(code) => log::warn!("the application exited with code {}", code),
+            Err(error) => log::error!("{}", error),
+        }
+    }
+    fn content_size(&self, constraints: Constraints) -> (f32, f32) {
+        self.child.measure(constraints)
+    }
+    fn arrange(&self, bounds: Rect) -> LayoutNode {
+        LayoutNode {
+            rect: bounds,
+            children: vec![self.child.arrange(self.child_rect(bounds))],
+        }
+    }
+    fn paint(&self, layout: &LayoutNode, list: &mut DisplayList) {
+        let bounds = layout.rect;
+        let style = self.style().or(&Style {
+            background: Some(Theme::current().palette.background),
+            ..Style::default()
+        });
+        list.with_clip(bounds, |list| {
+            style.paint(style.border_rect(bounds), list, |_, list| {
+                if let Some(node) = layout.children.first() {
+                    self.child.paint(node, list);
+                }
+            })
+        });
     }
 }
 
@@ -160,33 +857,509 @@ pub struct FlexLayout {
     pub width: Size,
     pub height: Size,
     pub children: Widgets,
+    pub style: Style,
+    /// Selected by `#id` in a [`Stylesheet`].
+    pub id: Option<String>,
+    /// Selected by `.class` in a [`Stylesheet`].
+    pub classes: Vec<String>,
+    computed_style: Option<Style>,
+    pub direction: FlexDirection,
+    pub wrap: FlexWrap,
+    pub justify_content: JustifyContent,
+    pub align_items: AlignItems,
+    pub gap: f32,
+}
+
+/// Wraps a child of a [`FlexLayout`] to set how it grows, shrinks and aligns.
+#[derive(Debug)]
+pub struct FlexItem {
+    pub child: Box<dyn Widget>,
+    pub grow: f32,
+    pub shrink: f32,
+    pub basis: Option<Size>,
+    pub align_self: Option<AlignItems>,
+}
+
+impl Default for FlexItem {
+    fn default() -> Self {
+        let flex = Flex::default();
+        Self {
+            child: Box::new(Label::default()),
+            grow: flex.grow,
+            shrink: flex.shrink,
+            basis: flex.basis,
+            align_self: flex.align_self,
+        }
+    }
 }
 
-#[derive(Debug, Clone, Default)]
+impl Widget for FlexItem {
+    fn _get_mut_size(&mut self) -> (&mut Size, &mut Size) {
+        self.child._get_mut_size()
+    }
+    fn _get_size(&self) -> (Size, Size) {
+        self.child._get_size()
+    }
+    fn render(self) {}
+    fn measure(&self, constraints: Constraints) -> (f32, f32) {
+        self.child.measure(constraints)
+    }
+    fn arrange(&self, bounds: Rect) -> LayoutNode {
+        LayoutNode {
+            rect: bounds,
+            children: vec![self.child.arrange(bounds)],
+        }
+    }
+    fn children(&self) -> &[Box<dyn Widget>] {
+        std::slice::from_ref(&self.child)
+    }
+    fn children_mut(&mut self) -> &mut [Box<dyn Widget>] {
+        std::slice::from_mut(&mut self.child)
+    }
+    fn paint(&self, layout: &LayoutNode, list: &mut DisplayList) {
+        if let Some(node) = layout.children.first() {
+            self.child.paint(node, list);
+        }
+    }
+    fn flex(&self) -> Flex {
+        Flex {
+            grow: self.grow,
+            shrink: self.shrink,
+            basis: self.basis.clone(),
+            align_self: self.align_self,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
 pub struct GridLayout {
     pub width: Size,
     pub height: Size,
+    pub children: Widgets,
+    pub style: Style,
+    /// Selected by `#id` in a [`Stylesheet`].
+    pub id: Option<String>,
+    /// Selected by `.class` in a [`Stylesheet`].
+    pub classes: Vec<String>,
+    computed_style: Option<Style>,
+    pub rows: Vec<Track>,
+    pub columns: Vec<Track>,
+    pub row_gap: f32,
+    pub column_gap: f32,
+    pub auto_rows: Track,
 }
 
-#[derive(Debug, Clone, Default)]
+/// Wraps a child of a [`GridLayout`] to place it in a specific cell.
+#[derive(Debug)]
+pub struct GridItem {
+    pub child: Box<dyn Widget>,
+    pub row: Option<usize>,
+    pub column: Option<usize>,
+    pub row_span: usize,
+    pub column_span: usize,
+}
+
+impl Default for GridItem {
+    fn default() -> Self {
+        let placement = GridPlacement::default();
+        Self {
+            child: Box::new(Label::default()),
+            row: placement.row,
+            column: placement.column,
+            row_span: placement.row_span,
+            column_span: placement.column_span,
+        }
+    }
+}
+
+impl Widget for GridItem {
+    fn _get_mut_size(&mut self) -> (&mut Size, &mut Size) {
+        self.child._get_mut_size()
+    }
+    fn _get_size(&self) -> (Size, Size) {
+        self.child._get_size()
+    }
+    fn render(self) {}
+    fn measure(&self, constraints: Constraints) -> (f32, f32) {
+        self.child.measure(constraints)
+    }
+    fn arrange(&self, bounds: Rect) -> LayoutNode {
+        LayoutNode {
+            rect: bounds,
+            children: vec![self.child.arrange(bounds)],
+        }
+    }
+    fn children(&self) -> &[Box<dyn Widget>] {
+        std::slice::from_ref(&self.child)
+    }
+    fn children_mut(&mut self) -> &mut [Box<dyn Widget>] {
+        std::slice::from_mut(&mut self.child)
+    }
+    fn paint(&self, layout: &LayoutNode, list: &mut DisplayList) {
+        if let Some(node) = layout.children.first() {
+            self.child.paint(node, list);
+        }
+    }
+    fn grid(&self) -> GridPlacement {
+        GridPlacement {
+            row: self.row,
+            column: self.column,
+            row_span: self.row_span,
+            column_span: self.column_span,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
 pub struct StackLayout {
     pub width: Size,
     pub height: Size,
+    pub children: Widgets,
+    pub style: Style,
+    /// Selected by `#id` in a [`Stylesheet`].
+    pub id: Option<String>,
+    /// Selected by `.class` in a [`Stylesheet`].
+    pub classes: Vec<String>,
+    computed_style: Option<Style>,
+    /// Where children without an anchor of their own are placed.
+    pub alignment: Anchor,
+}
+
+/// Wraps a child of a [`StackLayout`] to anchor, offset or raise it.
+#[derive(Debug)]
+pub struct StackItem {
+    pub child: Box<dyn Widget>,
+    pub anchor: Option<Anchor>,
+    pub offset_x: f32,
+    pub offset_y: f32,
+    pub z_index: i32,
+}
+
+impl Default for StackItem {
+    fn default() -> Self {
+        Self {
+            child: Box::new(Label::default()),
+            anchor: None,
+            offset_x: 0.0,
+            offset_y: 0.0,
+            z_index: 0,
+        }
+    }
+}
+
+impl Widget for StackItem {
+    fn _get_mut_size(&mut self) -> (&mut Size, &mut Size) {
+        self.child._get_mut_size()
+    }
+    fn _get_size(&self) -> (Size, Size) {
+        self.child._get_size()
+    }
+    fn render(self) {}
+    fn measure(&self, constraints: Constraints) -> (f32, f32) {
+        self.child.measure(constraints)
+    }
+    fn arrange(&self, bounds: Rect) -> LayoutNode {
+        LayoutNode {
+            rect: bounds,
+            children: vec![self.child.arrange(bounds)],
+        }
+    }
+    fn children(&self) -> &[Box<dyn Widget>] {
+        std::slice::from_ref(&self.child)
+    }
+    fn children_mut(&mut self) -> &mut [Box<dyn Widget>] {
+        std::slice::from_mut(&mut self.child)
+    }
+    fn paint(&self, layout: &LayoutNode, list: &mut DisplayList) {
+        if let Some(node) = layout.children.first() {
+            self.child.paint(node, list);
+        }
+    }
+    fn stack(&self) -> StackPlacement {
+        StackPlacement {
+            anchor: self.anchor,
+            offset_x: self.offset_x,
+            offset_y: self.offset_y,
+            z_index: self.z_index,
+        }
+    }
 }
 
 impl Widget for FlexLayout {
     common_widget!();
     fn render(self) {}
+    fn content_size(&self, constraints: Constraints) -> (f32, f32) {
+        let row = self.direction.is_row();
+        let (mut main, mut cross) = (0.0, 0.0f32);
+        for child in &self.children {
+            let (width, height) = child.measure(constraints);
+            let (child_main, child_cross) = if row {
+                (width, height)
+            } else {
+                (height, width)
+            };
+            main += child_main;
+            cross = cross.max(child_cross);
+        }
+        main += self.gap * (self.children.len() as f32 - 1.0).max(0.0);
+        if row {
+            (main, cross)
+        } else {
+            (cross, main)
+        }
+    }
+    fn children(&self) -> &[Box<dyn Widget>] {
+        &self.children
+    }
+    fn children_mut(&mut self) -> &mut [Box<dyn Widget>] {
+        &mut self.children
+    }
+    fn arrange(&self, bounds: Rect) -> LayoutNode {
+        let rects = self.compute_layout(self.style().content_rect(bounds));
+        arrange_children(&self.children, self.style().border_rect(bounds), rects)
+    }
+    fn paint(&self, layout: &LayoutNode, list: &mut DisplayList) {
+        self.style().paint(layout.rect, list, |_, list| {
+            for (child, node) in self.children.iter().zip(&layout.children) {
+                child.paint(node, list);
+            }
+        });
+    }
 }
 
 impl Widget for GridLayout {
     common_widget!();
     fn render(self) {}
+    fn content_size(&self, constraints: Constraints) -> (f32, f32) {
+        // Laid out in no space at all, only the content sizes the tracks.
+        grid(&self.container(), 0.0, 0.0, &self.items(constraints))
+            .iter()
+            .fold((0.0, 0.0), |(width, height), rect| {
+                (rect.right().max(width), rect.bottom().max(height))
+            })
+    }
+    fn children(&self) -> &[Box<dyn Widget>] {
+        &self.children
+    }
+    fn children_mut(&mut self) -> &mut [Box<dyn Widget>] {
+        &mut self.children
+    }
+    fn arrange(&self, bounds: Rect) -> LayoutNode {
+        let rects = self.compute_layout(self.style().content_rect(bounds));
+        arrange_children(&self.children, self.style().border_rect(bounds), rects)
+    }
+    fn paint(&self, layout: &LayoutNode, list: &mut DisplayList) {
+        self.style().paint(layout.rect, list, |_, list| {
+            for (child, node) in self.children.iter().zip(&layout.children) {
+                child.paint(node, list);
+            }
+        });
+    }
 }
 
 impl Widget for StackLayout {
     common_widget!();
     fn render(self) {}
+    fn content_size(&self, constraints: Constraints) -> (f32, f32) {
+        self.children
+            .iter()
+            .map(|child| child.measure(constraints))
+            .fold(
+                (0.0, 0.0),
+                |(width, height), (child_width, child_height)| {
+                    (child_width.max(width), child_height.max(height))
+                },
+            )
+    }
+    fn children(&self) -> &[Box<dyn Widget>] {
+        &self.children
+    }
+    fn children_mut(&mut self) -> &mut [Box<dyn Widget>] {
+        &mut self.children
+    }
+    fn arrange(&self, bounds: Rect) -> LayoutNode {
+        let rects = self.compute_layout(self.style().content_rect(bounds));
+        arrange_children(&self.children, self.style().border_rect(bounds), rects)
+    }
+    fn paint(&self, layout: &LayoutNode, list: &mut DisplayList) {
+        self.style().paint(layout.rect, list, |_, list| {
+            for index in self.paint_order() {
+                if let (Some(child), Some(node)) =
+                    (self.children.get(index), layout.children.get(index))
+                {
+                    child.paint(node, list);
+                }
+            }
+        });
+    }
+    fn paint_order(&self) -> Vec<usize> {
+        // Only the placements order the children, which need no measuring.
+        let items: Vec<StackChild> = self
+            .children
+            .iter()
+            .map(|child| StackChild {
+                placement: child.stack(),
+                ..StackChild::default()
+            })
+            .collect();
+        paint_order(&items)
+    }
+}
+
+impl FlexLayout {
+    fn container(&self) -> FlexContainer {
+        FlexContainer {
+            direction: self.direction,
+            wrap: self.wrap,
+            justify_content: self.justify_content,
+            align_items: self.align_items,
+            gap: self.gap,
+        }
+    }
+
+    /// The rectangles of the children when laid out within `bounds`.
+    pub fn compute_layout(&self, bounds: Rect) -> Vec<Rect> {
+        let row = self.direction.is_row();
+        let constraints = Constraints::loose(bounds.width, bounds.height);
+        let items: Vec<FlexChild> = self
+            .children
+            .iter()
+            .map(|child| {
+                let (width, height) = child.measure(constraints);
+                let (main, cross, main_space) = if row {
+                    (width, height, bounds.width)
+                } else {
+                    (height, width, bounds.height)
+                };
+                let flex = child.flex();
+                let (own_width, own_height) = child._get_size();
+                let (own_main, own_cross) = if row {
+                    (own_width, own_height)
+                } else {
+                    (own_height, own_width)
+                };
+                let fill = own_main.fill();
+                // A filling child starts out empty and grows into its share.
+                let (main, grow) = match fill {
+                    Some(fraction) if flex.basis.is_none() => (0.0, flex.grow.max(fraction)),
+                    _ => (main, flex.grow),
+                };
+                FlexChild {
+                    basis: flex.basis.map_or(main, |basis| basis.resolve(main_space)),
+                    // Only a child sized by its content can be stretched.
+                    cross: match own_cross {
+                        Size::Auto | Size::Fill(_) => None,
+                        _ => Some(cross),
+                    },
+                    content_cross: cross,
+                    grow,
+                    shrink: flex.shrink,
+                    align_self: flex.align_self,
+                }
+            })
+            .collect();
+
+        flex(&self.container(), bounds.width, bounds.height, &items)
+            .into_iter()
+            .map(|rect| {
+                Rect::new(
+                    bounds.x + rect.x,
+                    bounds.y + rect.y,
+                    rect.width,
+                    rect.height,
+                )
+            })
+            .collect()
+    }
+}
+
+impl GridLayout {
+    fn items(&self, constraints: Constraints) -> Vec<GridChild> {
+        self.children
+            .iter()
+            .map(|child| {
+                let (width, height) = child.measure(constraints);
+                GridChild {
+                    placement: child.grid(),
+                    width,
+                    height,
+                }
+            })
+            .collect()
+    }
+
+    fn container(&self) -> GridContainer {
+        GridContainer {
+            rows: self.rows.clone(),
+            columns: self.columns.clone(),
+            row_gap: self.row_gap,
+            column_gap: self.column_gap,
+            auto_rows: self.auto_rows,
+        }
+    }
+
+    /// The rectangles of the children when laid out within `bounds`.
+    pub fn compute_layout(&self, bounds: Rect) -> Vec<Rect> {
+        let items = self.items(Constraints::loose(bounds.width, bounds.height));
+        grid(&self.container(), bounds.width, bounds.height, &items)
+            .into_iter()
+            .map(|rect| {
+                Rect::new(
+                    bounds.x + rect.x,
+                    bounds.y + rect.y,
+                    rect.width,
+                    rect.height,
+                )
+            })
+            .collect()
+    }
+}
+
+impl StackLayout {
+    fn items(&self, bounds: Rect) -> Vec<StackChild> {
+        let constraints = Constraints::loose(bounds.width, bounds.height);
+        self.children
+            .iter()
+            .map(|child| {
+                let (width, height) = child.measure(constraints);
+                StackChild {
+                    placement: child.stack(),
+                    width,
+                    height,
+                }
+            })
+            .collect()
+    }
+
+    /// The rectangles of the children when laid out within `bounds`.
+    pub fn compute_layout(&self, bounds: Rect) -> Vec<Rect> {
+        let container = StackContainer {
+            alignment: self.alignment,
+        };
+        stack(&container, bounds.width, bounds.height, &self.items(bounds))
+            .into_iter()
+            .map(|rect| {
+                Rect::new(
+                    bounds.x + rect.x,
+                    bounds.y + rect.y,
+                    rect.width,
+                    rect.height,
+                )
+            })
+            .collect()
+    }
+}
+
+/// Arranges every child of a container into the rectangle computed for it.
+fn arrange_children(children: &Widgets, bounds: Rect, rects: Vec<Rect>) -> LayoutNode {
+    LayoutNode {
+        rect: bounds,
+        children: children
+            .iter()
+            .zip(rects)
+            .map(|(child, rect)| child.arrange(rect))
+            .collect(),
+    }
 }
 
 impl Container for FlexLayout {}